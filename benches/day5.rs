@@ -0,0 +1,83 @@
+use aoc_2022::day5::{Command, Crane, CraneModel, Crate};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+/// Builds a crane with `stacks` stacks of `height` crates and `commands` commands that each move
+/// a large share of a stack, using a fixed linear congruential generator so every run is the same.
+/// With `fragment` set, three commands in four move a single crate instead, which cuts the stacks
+/// into lots of short runs for the big moves to carry around.
+fn large_crane(stacks: usize, height: usize, commands: usize, fragment: bool) -> Crane {
+    let mut state = 0x2022_u64;
+    let mut next = move |bound: usize| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) as usize % bound
+    };
+
+    let initial: Vec<Vec<Crate>> = (0..stacks)
+        .map(|i| {
+            (0..height)
+                .map(|j| Crate::from(char::from(b'A' + ((i + j) % 26) as u8)))
+                .collect()
+        })
+        .collect();
+
+    let mut heights = vec![height; stacks];
+    let mut moves = Vec::with_capacity(commands);
+    for i in 0..commands {
+        let from = next(stacks);
+        let to = (from + 1 + next(stacks - 1)) % stacks;
+        let amount = if fragment && i % 4 != 0 {
+            heights[from].min(1)
+        } else {
+            next(heights[from] + 1)
+        };
+        heights[from] -= amount;
+        heights[to] += amount;
+        moves.push(Command {
+            amount,
+            from: from + 1,
+            to: to + 1,
+        });
+    }
+
+    Crane::new(initial, moves)
+}
+
+/// Runs every command of a crane with the given model.
+type Process = fn(&mut Crane, CraneModel);
+
+fn bench_process(c: &mut Criterion) {
+    for (workload, crane) in [
+        ("large moves", large_crane(9, 20_000, 5_000, false)),
+        ("fragmented", large_crane(9, 20_000, 20_000, true)),
+    ] {
+        for (name, model) in [
+            ("9000", CraneModel::CrateMover9000),
+            ("9001", CraneModel::CrateMover9001),
+        ] {
+            let mut group = c.benchmark_group(format!("day5 {workload} {name}"));
+            group.sample_size(10);
+            let solvers: [(&str, Process); 3] = [
+                ("original", |crane, model| {
+                    crane.process_original(model).unwrap()
+                }),
+                ("vec", |crane, model| crane.process(model).unwrap()),
+                ("runs", |crane, model| crane.process_runs(model).unwrap()),
+            ];
+            for (solver, process) in solvers {
+                group.bench_function(solver, |b| {
+                    b.iter_batched(
+                        || crane.clone(),
+                        |mut crane| process(&mut crane, model),
+                        BatchSize::LargeInput,
+                    )
+                });
+            }
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, bench_process);
+criterion_main!(benches);
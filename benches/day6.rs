@@ -0,0 +1,41 @@
+use aoc_2022::day6::{find_chars, find_chars_hashmap};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+/// Builds a stream of `len` characters drawn from only 13 letters, so no 14 in a row can be
+/// distinct, followed by a start-of-message marker right at the end.
+fn long_stream(len: usize) -> String {
+    let mut state = 0x2022_u64;
+    let mut stream: String = (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            char::from(b'a' + ((state >> 33) % 13) as u8)
+        })
+        .collect();
+    stream.push_str("nopqrstuvwxyzA");
+    stream
+}
+
+fn bench_find_chars(c: &mut Criterion) {
+    let stream = long_stream(4 << 20);
+    let mut group = c.benchmark_group("day6 4MiB");
+    group.throughput(Throughput::Bytes(stream.len() as u64));
+    group.sample_size(10);
+    group.bench_function("hashmap", |b| {
+        b.iter(|| find_chars_hashmap::<14>(&stream).unwrap())
+    });
+    group.bench_function("window, bytes", |b| {
+        b.iter(|| find_chars::<14>(&stream).unwrap())
+    });
+    // a single non-ASCII character sends the whole search through the window a character at a
+    // time
+    let unicode = format!("é{stream}");
+    group.bench_function("window, chars", |b| {
+        b.iter(|| find_chars::<14>(&unicode).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_find_chars);
+criterion_main!(benches);
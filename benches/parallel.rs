@@ -0,0 +1,62 @@
+use aoc_2022::{day1, day2, day3, day4, generate};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+type Solvers = [(&'static str, fn(&str) -> u64); 2];
+
+/// Compares each day's sequential solver with its parallel one on a generated input of a few
+/// dozen megabytes.
+fn bench_parallel(c: &mut Criterion) {
+    let days: [(&str, String, Solvers); 4] = [
+        (
+            "day1",
+            generate::day1(1, 1_000_000).unwrap().input,
+            [
+                ("sequential", |input| u64::from(day1::part2(input).unwrap())),
+                ("parallel", |input| {
+                    u64::from(day1::part2_parallel(input).unwrap())
+                }),
+            ],
+        ),
+        (
+            "day2",
+            generate::day2(2, 4_000_000).unwrap().input,
+            [
+                ("sequential", day2::part2),
+                ("parallel", day2::part2_parallel),
+            ],
+        ),
+        (
+            "day3",
+            generate::day3(3, 200_000).unwrap().input,
+            [
+                ("sequential", |input| u64::from(day3::part2(input).unwrap())),
+                ("parallel", |input| {
+                    u64::from(day3::part2_parallel(input).unwrap())
+                }),
+            ],
+        ),
+        (
+            "day4",
+            generate::day4(4, 2_000_000).unwrap().input,
+            [
+                ("sequential", |input| u64::from(day4::part2(input).unwrap())),
+                ("parallel", |input| {
+                    u64::from(day4::part2_parallel(input).unwrap())
+                }),
+            ],
+        ),
+    ];
+
+    for (day, input, solvers) in &days {
+        let mut group = c.benchmark_group(format!("{day} part2"));
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.sample_size(10);
+        for (name, solver) in solvers {
+            group.bench_function(*name, |b| b.iter(|| solver(input)));
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_parallel);
+criterion_main!(benches);
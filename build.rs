@@ -0,0 +1,117 @@
+//! Generates a test for every example in the puzzle descriptions pasted into each day's module
+//! docs, so that a new day is checked against its example without writing the test by hand.
+//!
+//! The example input is the block of paragraphs that follows the first paragraph mentioning an
+//! example and ending with a colon, up to the next paragraph of prose (one whose last line ends
+//! like a sentence).  The expected answer is the last number outside of parentheses in the last
+//! paragraph of each part that mentions the example.  Part two is looked for after a
+//! `--- Part Two ---` heading, and reuses part one's example unless it gives one of its own.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut days: Vec<(u32, String)> = fs::read_dir("src")
+        .expect("Failed to read src")
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let day = path
+                .file_stem()?
+                .to_str()?
+                .strip_prefix("day")?
+                .parse()
+                .ok()?;
+            (path.extension()? == "rs").then(|| (day, fs::read_to_string(&path).unwrap()))
+        })
+        .collect();
+    days.sort();
+
+    let mut tests = String::new();
+    for (day, source) in days {
+        let docs = module_docs(&source);
+        let (part1, part2) = match docs.split_once("--- Part Two ---") {
+            Some((part1, part2)) => (part1, Some(part2)),
+            None => (docs.as_str(), None),
+        };
+
+        let Some(example) = example(part1) else {
+            continue;
+        };
+        if let Some(answer) = answer(part1) {
+            write_test(&mut tests, day, 1, &example, &answer);
+        }
+        if let Some(part2) = part2 {
+            let example = self::example(part2).unwrap_or(example);
+            if let Some(answer) = answer(part2) {
+                write_test(&mut tests, day, 2, &example, &answer);
+            }
+        }
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("examples.rs");
+    fs::write(out, tests).expect("Failed to write the example tests");
+}
+
+/// The text of the leading `//!` comments.
+fn module_docs(source: &str) -> String {
+    source
+        .lines()
+        .map_while(|line| line.strip_prefix("//!"))
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn paragraphs(docs: &str) -> impl Iterator<Item = &str> {
+    docs.split("\n\n").map(str::trim).filter(|p| !p.is_empty())
+}
+
+fn is_prose(paragraph: &str) -> bool {
+    paragraph.ends_with(['.', ':', '?', '!'])
+}
+
+fn mentions_example(paragraph: &str) -> bool {
+    paragraph.to_lowercase().contains("example")
+}
+
+fn example(docs: &str) -> Option<String> {
+    let example: Vec<&str> = paragraphs(docs)
+        .skip_while(|p| !(mentions_example(p) && p.ends_with(':')))
+        .skip(1)
+        .take_while(|p| !is_prose(p))
+        .collect();
+    (!example.is_empty()).then(|| example.join("\n\n") + "\n")
+}
+
+fn answer(docs: &str) -> Option<String> {
+    let paragraph = paragraphs(docs)
+        .filter(|p| is_prose(p) && mentions_example(p))
+        .last()?;
+
+    let mut depth = 0;
+    let mut number = String::new();
+    let mut last = None;
+    for c in paragraph.chars().chain([' ']) {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '0'..='9' if depth == 0 => number.push(c),
+            _ if !number.is_empty() => last = Some(std::mem::take(&mut number)),
+            _ => {}
+        }
+    }
+    last
+}
+
+fn write_test(tests: &mut String, day: u32, part: u32, example: &str, answer: &str) {
+    writeln!(
+        tests,
+        "#[test]\n\
+         fn day{day}_part{part}() {{\n    \
+             let solver = crate::solvers::get({day}).unwrap().part{part};\n    \
+             assert_eq!(solver({example:?}).unwrap(), {answer:?});\n\
+         }}\n"
+    )
+    .unwrap();
+}
@@ -0,0 +1,11 @@
+#![no_main]
+
+use aoc_2022::day3;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = day3::part1(input);
+        let _ = day3::part2(input);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use aoc_2022::day4;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = day4::part1(input);
+        let _ = day4::part2(input);
+    }
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use aoc_2022::day5::{Crane, CraneModel};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(crane) = Crane::parse(input) else {
+        return;
+    };
+
+    let _ = crane.validate();
+    let _ = crane.render();
+    for model in [CraneModel::CrateMover9000, CraneModel::CrateMover9001] {
+        let mut simulated = crane.clone();
+        let _ = simulated.process(model);
+        let mut runs = crane.clone();
+        let _ = runs.process_runs(model);
+        let _ = runs.read_stack_tops_string();
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use aoc_2022::day6::{self, framing, MarkerDetector};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut detector = MarkerDetector::new();
+    let _ = detector.push(data);
+    for message in framing::messages(data) {
+        let _ = message.payload();
+    }
+
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = day6::part1(input);
+        let _ = day6::part2(input);
+        let _ = day6::part1_hashmap(input);
+        let _ = day6::part2_hashmap(input);
+        let _ = day6::longest_distinct(input);
+        let _ = day6::markers(input, 4).count();
+    }
+});
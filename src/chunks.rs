@@ -0,0 +1,173 @@
+//! Splits an input into pieces of similar size at record boundaries, so that the pieces can be
+//! solved in parallel and their results combined.
+//!
+//! Pieces are cut at a separator, which is left out of both sides of the cut, so splitting an input
+//! at `"\n"` gives pieces whose lines are exactly the input's lines, and splitting at `"\n\n"` gives
+//! pieces whose blank-line separated blocks are exactly the input's blocks.
+
+use rayon::prelude::*;
+
+/// How many pieces to split an input into: a few per thread, so that uneven pieces even out.
+pub(crate) fn pieces() -> usize {
+    rayon::current_num_threads() * 4
+}
+
+/// Splits `input` into at most `pieces` pieces at occurrences of `separator`, which must be a run
+/// of a single repeated character such as `"\n"` or `"\n\n"`.
+pub(crate) fn split<'a>(input: &'a str, pieces: usize, separator: &str) -> Vec<&'a str> {
+    let cuts = cuts(input, pieces, separator);
+    pieces_between(input, &cuts, separator.len())
+}
+
+/// Splits `input` into at most `pieces` pieces of whole lines, with each piece but the last
+/// holding a multiple of `group` lines.  Unlike [`split`], each piece keeps the newline at its end,
+/// as a cut moved to the end of a group might otherwise leave a piece ending in an empty line that
+/// `lines` wouldn't see.
+pub(crate) fn split_groups(input: &str, pieces: usize, group: usize) -> Vec<&str> {
+    let mut ends: Vec<usize> = cuts(input, pieces, "\n")
+        .into_iter()
+        .map(|cut| cut + 1)
+        .collect();
+
+    // count the lines in each piece in parallel, then move the end of each piece forward to the
+    // end of the group it falls in
+    let mut starts = vec![0];
+    starts.extend(&ends);
+    let lines: Vec<usize> = starts
+        .par_windows(2)
+        .map(|w| newlines(&input[w[0]..w[1]]))
+        .collect();
+    let mut before = 0;
+    for (end, lines) in ends.iter_mut().zip(lines) {
+        before += lines;
+        for _ in 0..(group - before % group) % group {
+            match input[*end..].find('\n') {
+                Some(next) => *end += next + 1,
+                None => {
+                    *end = input.len();
+                    break;
+                }
+            }
+        }
+    }
+    ends.dedup();
+    ends.retain(|&end| end < input.len());
+
+    pieces_between(input, &ends, 0)
+}
+
+fn newlines(text: &str) -> usize {
+    text.bytes().filter(|&b| b == b'\n').count()
+}
+
+/// The positions of the separators to cut at, in order.
+fn cuts(input: &str, pieces: usize, separator: &str) -> Vec<usize> {
+    let size = input.len() / pieces.max(1);
+    let repeated = separator.as_bytes()[0];
+    let mut cuts: Vec<usize> = vec![];
+    let mut from = 0;
+    while size > 0 && from + size < input.len() {
+        let mut start = from + size;
+        while !input.is_char_boundary(start) {
+            start += 1;
+        }
+        let Some(found) = input[start..].find(separator) else {
+            break;
+        };
+        // a longer run of the separator character is split from its start, so cut there
+        let mut cut = start + found;
+        while cut > from && input.as_bytes()[cut - 1] == repeated {
+            cut -= 1;
+        }
+        if cut > from || (cut == 0 && cuts.is_empty()) {
+            cuts.push(cut);
+        }
+        from = cut.max(from) + separator.len();
+    }
+    cuts
+}
+
+fn pieces_between<'a>(input: &'a str, cuts: &[usize], separator: usize) -> Vec<&'a str> {
+    let mut pieces = Vec::with_capacity(cuts.len() + 1);
+    let mut start = 0;
+    for &cut in cuts {
+        pieces.push(&input[start..cut]);
+        start = cut + separator;
+    }
+    pieces.push(&input[start..]);
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day1, day2, day3, day4, generate};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_split() {
+        let input = "1\n2\n\n3\n\n\n\n4\n5\n\n6";
+        for pieces in 1..=input.len() + 1 {
+            let split = split(input, pieces, "\n\n");
+            assert_eq!(split.join("\n\n"), input);
+            let blocks: Vec<&str> = split.iter().flat_map(|p| p.split("\n\n")).collect();
+            assert_eq!(blocks, input.split("\n\n").collect::<Vec<_>>(), "{pieces}");
+        }
+    }
+
+    #[test]
+    fn test_split_groups() {
+        let input = generate::day3(7, 50).unwrap().input + "abcdef\n";
+        for pieces in 1..=40 {
+            let split = split_groups(&input, pieces, 3);
+            assert_eq!(split.concat(), input, "{pieces}");
+            for piece in &split[..split.len() - 1] {
+                assert_eq!(piece.lines().count() % 3, 0, "{pieces}");
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn day1_matches(seed in any::<u64>(), elves in 3..100usize, pieces in 1..40usize) {
+            let input = generate::day1(seed, elves).unwrap().input;
+            prop_assert_eq!(day1::part1_split(&input, pieces).unwrap(), day1::part1(&input).unwrap());
+            prop_assert_eq!(day1::part2_split(&input, pieces).unwrap(), day1::part2(&input).unwrap());
+        }
+
+        #[test]
+        fn day1_errors_match(input in "[0-9\n]{0,40}", pieces in 1..10usize) {
+            let sequential = day1::part1(&input).map_err(|e| e.to_string());
+            let parallel = day1::part1_split(&input, pieces).map_err(|e| e.to_string());
+            prop_assert_eq!(parallel, sequential);
+            let sequential = day1::part2(&input).map_err(|e| e.to_string());
+            let parallel = day1::part2_split(&input, pieces).map_err(|e| e.to_string());
+            prop_assert_eq!(parallel, sequential);
+        }
+
+        #[test]
+        fn day2_matches(rounds in prop::collection::vec("([ABCD] [XYZ])?", 0..100), pieces in 1..40usize) {
+            let input = rounds.join("\n");
+            prop_assert_eq!(day2::part1_split(&input, pieces), day2::part1(&input));
+            prop_assert_eq!(day2::part2_split(&input, pieces), day2::part2(&input));
+        }
+
+        #[test]
+        fn day3_matches(seed in any::<u64>(), groups in 0..40usize, extra in "[a-z\n]{0,10}", pieces in 1..40usize) {
+            let input = generate::day3(seed, groups).unwrap().input + &extra;
+            let sequential = day3::part1(&input).map_err(|e| e.to_string());
+            prop_assert_eq!(day3::part1_split(&input, pieces).map_err(|e| e.to_string()), sequential);
+            let sequential = day3::part2(&input).map_err(|e| e.to_string());
+            prop_assert_eq!(day3::part2_split(&input, pieces).map_err(|e| e.to_string()), sequential);
+        }
+
+        #[test]
+        fn day4_matches(seed in any::<u64>(), pairs in 0..100usize, extra in "[0-9,\n-]{0,10}", pieces in 1..40usize) {
+            let input = generate::day4(seed, pairs).unwrap().input + &extra;
+            let sequential = day4::part1(&input).map_err(|e| e.to_string());
+            prop_assert_eq!(day4::part1_split(&input, pieces).map_err(|e| e.to_string()), sequential);
+            let sequential = day4::part2(&input).map_err(|e| e.to_string());
+            prop_assert_eq!(day4::part2_split(&input, pieces).map_err(|e| e.to_string()), sequential);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! A client for the Advent of Code website, for downloading puzzle inputs and submitting answers.
+//!
+//! Requests are authenticated with the `session` cookie from a logged in browser.  Inputs never
+//! change once published, so each one is downloaded once and read from `input/2022` after that,
+//! and requests made by one client are spaced out so that fetching several days doesn't hammer
+//! the server.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::submit::Verdict;
+
+/// Where the real puzzles live.
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// How long to wait between requests by default.
+pub const INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    inputs: PathBuf,
+    interval: Duration,
+    last_request: Option<Instant>,
+}
+
+/// Where a day's input came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Cache,
+    Server,
+}
+
+impl Client {
+    /// A client logged in with `session` that keeps inputs in `inputs`.
+    pub fn new(session: &str, inputs: impl Into<PathBuf>) -> Client {
+        Client {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("aoc-2022/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(30))
+                .build(),
+            base_url: BASE_URL.to_string(),
+            session: session.trim().to_string(),
+            inputs: inputs.into(),
+            interval: INTERVAL,
+            last_request: None,
+        }
+    }
+
+    /// Talks to the server at `base_url` instead of the real one.
+    pub fn with_base_url(mut self, base_url: &str) -> Client {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Waits at least `interval` between requests.
+    pub fn with_interval(mut self, interval: Duration) -> Client {
+        self.interval = interval;
+        self
+    }
+
+    /// The file `day`'s input is kept in.
+    pub fn input_path(&self, day: u32) -> PathBuf {
+        self.inputs.join(format!("day{day}.txt"))
+    }
+
+    /// Makes sure `day`'s input is on disk, downloading it unless it's already there or `force`
+    /// is set.  An empty file, as left by `new-day`, counts as missing.
+    pub fn fetch_input(&mut self, day: u32, force: bool) -> Result<(PathBuf, Source)> {
+        let path = self.input_path(day);
+        if !force && fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+            return Ok((path, Source::Cache));
+        }
+
+        let request = self.agent.get(&self.url(&format!("/2022/day/{day}/input")));
+        let input = self.send(day, request, None)?;
+        if input.trim().is_empty() {
+            bail!("The server sent an empty input for day {day}");
+        }
+        write(&path, &input)?;
+        Ok((path, Source::Server))
+    }
+
+    /// Submits `answer` to `part` of `day`'s puzzle.
+    pub fn submit_answer(&mut self, day: u32, part: u32, answer: &str) -> Result<Verdict> {
+        if !(1..=2).contains(&part) {
+            bail!("There is no part {part}, only parts 1 and 2");
+        }
+        let request = self
+            .agent
+            .post(&self.url(&format!("/2022/day/{day}/answer")));
+        let level = part.to_string();
+        let page = self.send(day, request, Some(&[("level", &level), ("answer", answer)]))?;
+        Verdict::parse(&page)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    /// Sleeps until the interval since the last request has passed.
+    fn wait_turn(&mut self) {
+        if let Some(last) = self.last_request {
+            thread::sleep(self.interval.saturating_sub(last.elapsed()));
+        }
+        self.last_request = Some(Instant::now());
+    }
+
+    /// Sends `request` with the session cookie, and `form` as its body if given, turning error
+    /// statuses into errors that say what went wrong.
+    fn send(
+        &mut self,
+        day: u32,
+        request: ureq::Request,
+        form: Option<&[(&str, &str)]>,
+    ) -> Result<String> {
+        if self.session.is_empty() {
+            bail!("No session cookie given; copy the `session` cookie from a logged in browser");
+        }
+        self.wait_turn();
+
+        let url = request.url().to_string();
+        let request = request.set("Cookie", &format!("session={}", self.session));
+        let response = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+        match response {
+            Ok(response) => Ok(response
+                .into_string()
+                .with_context(|| format!("Failed to read the response from {url}"))?),
+            Err(ureq::Error::Status(400, _)) => bail!(
+                "The session cookie was rejected; it may have expired, so log in again and copy a \
+                 fresh one"
+            ),
+            Err(ureq::Error::Status(404, _)) => {
+                bail!("Day {day} doesn't exist or hasn't been unlocked yet")
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                bail!("The server failed with status {status}: {}", body.trim())
+            }
+            Err(e) => Err(e).with_context(|| format!("Failed to reach {url}")),
+        }
+    }
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockServer, SESSION};
+
+    fn client(server: &MockServer, name: &str) -> Client {
+        let inputs = server.dir().join(name);
+        Client::new(SESSION, inputs)
+            .with_base_url(&server.url())
+            .with_interval(Duration::ZERO)
+    }
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let server = MockServer::start();
+        let mut client = client(&server, "cache");
+
+        let (path, source) = client.fetch_input(3, false).unwrap();
+        assert_eq!(source, Source::Server);
+        assert_eq!(fs::read_to_string(&path).unwrap(), MockServer::input(3));
+        assert_eq!(server.requests(), 1);
+
+        assert_eq!(
+            client.fetch_input(3, false).unwrap(),
+            (path.clone(), Source::Cache)
+        );
+        assert_eq!(server.requests(), 1);
+        assert_eq!(
+            client.fetch_input(3, true).unwrap(),
+            (path.clone(), Source::Server)
+        );
+        assert_eq!(server.requests(), 2);
+
+        // an empty placeholder is replaced
+        fs::write(&path, "").unwrap();
+        assert_eq!(client.fetch_input(3, false).unwrap().1, Source::Server);
+    }
+
+    #[test]
+    fn test_errors() {
+        let server = MockServer::start();
+        let mut client = client(&server, "errors");
+
+        let err = client.fetch_input(30, false).unwrap_err().to_string();
+        assert_eq!(err, "Day 30 doesn't exist or hasn't been unlocked yet");
+        let err = client
+            .fetch_input(MockServer::BROKEN_DAY, false)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "The server failed with status 500: Internal Server Error"
+        );
+        assert!(!client.input_path(MockServer::BROKEN_DAY).exists());
+
+        let mut client = Client::new("expired", server.dir().join("errors"))
+            .with_base_url(&server.url())
+            .with_interval(Duration::ZERO);
+        let err = client.fetch_input(1, false).unwrap_err().to_string();
+        assert!(err.starts_with("The session cookie was rejected"), "{err}");
+        assert!(!client.input_path(1).exists());
+
+        let mut client = Client::new("  \n", server.dir().join("errors"));
+        assert!(client.fetch_input(1, false).is_err());
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let server = MockServer::start();
+        let interval = Duration::from_millis(200);
+        let mut client = client(&server, "rate").with_interval(interval);
+
+        let start = Instant::now();
+        for day in 1..=3 {
+            client.fetch_input(day, false).unwrap();
+        }
+        assert!(start.elapsed() >= interval * 2);
+        assert_eq!(server.requests(), 3);
+    }
+}
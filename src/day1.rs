@@ -54,35 +54,116 @@
 //!
 //! Find the Elf carrying the most Calories. How many total Calories is that Elf carrying?
 
-use aoc_runner_derive::aoc;
+use anyhow::{anyhow, bail, Result};
+use aoc_runner_derive::{aoc, aoc_generator};
+use rayon::prelude::*;
+
+use crate::{chunks, normalize::normalize_for_day};
+
+/// Adds up the calories carried by each elf.  Elves are separated by blank lines.
+fn totals(input: &str) -> Result<Vec<u32>> {
+    input
+        .split("\n\n")
+        .map(|elf| {
+            elf.lines().try_fold(0u32, |total, line| {
+                let calories = line
+                    .parse::<u32>()
+                    .map_err(|e| anyhow!("Failed to parse calories {line:?}: {e}"))?;
+                total
+                    .checked_add(calories)
+                    .ok_or_else(|| anyhow!("Too many calories carried by one elf"))
+            })
+        })
+        .collect()
+}
+
+/// Normalizes the input before the cargo-aoc runner hands it to any of the solvers below.
+#[aoc_generator(day1)]
+#[aoc_generator(day1, part1, Parallel)]
+#[aoc_generator(day1, part2, Parallel)]
+fn generator(input: &str) -> String {
+    normalize_for_day(1, input)
+}
 
 /// Computes the solution to day 1, part 1 with the given input. Example usage:
 ///
 /// ```rust
 /// let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
-/// assert_eq!(aoc_2022::day1::part1(input), 24000);
+/// assert_eq!(aoc_2022::day1::part1(input).unwrap(), 24000);
 /// ```
 #[aoc(day1, part1)]
-pub fn part1(input: &str) -> u32 {
-    input
-        .split("\n\n")
-        .map(|s| s.lines().map(|x| str::parse::<u32>(x).unwrap()).sum())
-        .max()
-        .unwrap_or_default()
+pub fn part1(input: &str) -> Result<u32> {
+    Ok(totals(input)?.into_iter().max().unwrap_or_default())
 }
 
 /// Computes the solution to day 1, part 2 with the given input. Example usage:
 ///
 /// ```rust
 /// let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
-/// assert_eq!(aoc_2022::day1::part2(input), 45000);
+/// assert_eq!(aoc_2022::day1::part2(input).unwrap(), 45000);
+/// assert!(aoc_2022::day1::part2("1000\n\n2000").is_err());
 /// ```
 #[aoc(day1, part2)]
-pub fn part2(input: &str) -> u32 {
-    let mut data: Vec<u32> = input
-        .split("\n\n")
-        .map(|s| s.lines().map(|x| str::parse::<u32>(x).unwrap()).sum())
-        .collect();
+pub fn part2(input: &str) -> Result<u32> {
+    let mut data = totals(input)?;
+    if data.len() < 3 {
+        bail!("Need at least three elves, but only found {}", data.len());
+    }
     data.sort_by_key(|&x| std::cmp::Reverse(x));
-    data[0..3].iter().sum()
+    data[0..3]
+        .iter()
+        .try_fold(0u32, |sum, &x| sum.checked_add(x))
+        .ok_or_else(|| anyhow!("Too many calories carried by the top three elves"))
+}
+
+#[aoc(day1, part1, Parallel)]
+pub fn part1_parallel(input: &str) -> Result<u32> {
+    part1_split(input, chunks::pieces())
+}
+
+#[aoc(day1, part2, Parallel)]
+pub fn part2_parallel(input: &str) -> Result<u32> {
+    part2_split(input, chunks::pieces())
+}
+
+/// Solves part 1 by finding the most calories in each of up to `pieces` pieces of the input at
+/// once.
+pub(crate) fn part1_split(input: &str, pieces: usize) -> Result<u32> {
+    let most: Vec<Result<u32>> = chunks::split(input, pieces, "\n\n")
+        .par_iter()
+        .map(|piece| part1(piece))
+        .collect();
+    most.into_iter()
+        .try_fold(0, |most, piece| Ok(most.max(piece?)))
+}
+
+/// Solves part 2 by finding the top three elves in each of up to `pieces` pieces of the input at
+/// once, then the top three of those.
+pub(crate) fn part2_split(input: &str, pieces: usize) -> Result<u32> {
+    let tops: Vec<Result<(usize, Vec<u32>)>> = chunks::split(input, pieces, "\n\n")
+        .par_iter()
+        .map(|piece| {
+            let mut totals = totals(piece)?;
+            let elves = totals.len();
+            totals.sort_by_key(|&x| std::cmp::Reverse(x));
+            totals.truncate(3);
+            Ok((elves, totals))
+        })
+        .collect();
+
+    let mut elves = 0;
+    let mut top = vec![];
+    for piece in tops {
+        let (piece_elves, piece_top) = piece?;
+        elves += piece_elves;
+        top.extend(piece_top);
+    }
+    if elves < 3 {
+        bail!("Need at least three elves, but only found {elves}");
+    }
+    top.sort_by_key(|&x| std::cmp::Reverse(x));
+    top[0..3]
+        .iter()
+        .try_fold(0u32, |sum, &x| sum.checked_add(x))
+        .ok_or_else(|| anyhow!("Too many calories carried by the top three elves"))
 }
@@ -28,11 +28,14 @@
 //!
 //! What would your total score be if everything goes exactly according to your strategy guide?
 
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     branch::alt, bytes::complete::tag, character::complete::space1, combinator::map,
     sequence::tuple, IResult,
 };
+use rayon::prelude::*;
+
+use crate::{chunks, normalize::normalize_for_day};
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Throw {
@@ -150,6 +153,14 @@ impl Round {
     }
 }
 
+/// Normalizes the input before the cargo-aoc runner hands it to any of the solvers below.
+#[aoc_generator(day2)]
+#[aoc_generator(day2, part1, Parallel)]
+#[aoc_generator(day2, part2, Parallel)]
+fn generator(input: &str) -> String {
+    normalize_for_day(2, input)
+}
+
 /// Simulates a set of rounds according to the rules of part 1.  Example:
 /// ```rust
 /// # use aoc_2022::day2::*;
@@ -157,7 +168,7 @@ impl Round {
 /// assert_eq!(part1(input), 15);
 /// ```
 #[aoc(day2, part1)]
-pub fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> u64 {
     input
         .lines()
         .map(Round::parse_1)
@@ -165,7 +176,7 @@ pub fn part1(input: &str) -> u32 {
             Ok((_, round)) => Some(round),
             Err(_) => None,
         })
-        .map(|r| r.score())
+        .map(|r| u64::from(r.score()))
         .sum()
 }
 
@@ -176,7 +187,7 @@ pub fn part1(input: &str) -> u32 {
 /// assert_eq!(part2(input), 12);
 /// ```
 #[aoc(day2, part2)]
-pub fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> u64 {
     input
         .lines()
         .map(Round::parse_2)
@@ -184,6 +195,32 @@ pub fn part2(input: &str) -> u32 {
             Ok((_, round)) => Some(round),
             Err(_) => None,
         })
-        .map(|r| r.score())
+        .map(|r| u64::from(r.score()))
+        .sum()
+}
+
+#[aoc(day2, part1, Parallel)]
+pub fn part1_parallel(input: &str) -> u64 {
+    part1_split(input, chunks::pieces())
+}
+
+#[aoc(day2, part2, Parallel)]
+pub fn part2_parallel(input: &str) -> u64 {
+    part2_split(input, chunks::pieces())
+}
+
+/// Solves part 1 by scoring up to `pieces` pieces of the input at once.
+pub(crate) fn part1_split(input: &str, pieces: usize) -> u64 {
+    chunks::split(input, pieces, "\n")
+        .par_iter()
+        .map(|piece| part1(piece))
+        .sum()
+}
+
+/// Solves part 2 by scoring up to `pieces` pieces of the input at once.
+pub(crate) fn part2_split(input: &str, pieces: usize) -> u64 {
+    chunks::split(input, pieces, "\n")
+        .par_iter()
+        .map(|piece| part2(piece))
         .sum()
 }
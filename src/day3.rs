@@ -1,21 +1,26 @@
-use aoc_runner_derive::aoc;
+use anyhow::{anyhow, Result};
+use aoc_runner_derive::{aoc, aoc_generator};
+use rayon::prelude::*;
+
+use crate::{chunks, normalize::normalize_for_day};
 
 /// Finds the first common ascii character in two strings.
 /// ```rust
 /// # use aoc_2022::day3::*;
-/// assert_eq!(common_element("abc", "cde"), 'c');
-/// assert_eq!(common_element("bcd", "def"), 'd');
-/// assert_eq!(common_element("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn", "ttgJtRGJQctTZtZT"), 'Z');
+/// assert_eq!(common_element("abc", "cde"), Some('c'));
+/// assert_eq!(common_element("bcd", "def"), Some('d'));
+/// assert_eq!(common_element("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn", "ttgJtRGJQctTZtZT"), Some('Z'));
+/// assert_eq!(common_element("abc", "def"), None);
 /// ```
-pub fn common_element(a: &str, b: &str) -> char {
+pub fn common_element(a: &str, b: &str) -> Option<char> {
     for x in a.bytes() {
         for y in b.bytes() {
             if x == y {
-                return char::from(x);
+                return Some(char::from(x));
             }
         }
     }
-    unreachable!()
+    None
 }
 
 /// Finds a common character in all three strings.
@@ -27,7 +32,7 @@ pub fn common_element(a: &str, b: &str) -> char {
 ///         "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
 ///         "PmmdzqPrVvPwwTWBwg"
 ///     ),
-///     'r'
+///     Some('r')
 /// );
 /// assert_eq!(
 ///     common_element_2(
@@ -35,21 +40,22 @@ pub fn common_element(a: &str, b: &str) -> char {
 ///         "ttgJtRGJQctTZtZT",
 ///         "CrZsJsPPZsGzwwsLwLmpwMDw"
 ///     ),
-///     'Z'
+///     Some('Z')
 /// );
+/// assert_eq!(common_element_2("abc", "bcd", "def"), None);
 /// ```
-pub fn common_element_2(a: &str, b: &str, c: &str) -> char {
+pub fn common_element_2(a: &str, b: &str, c: &str) -> Option<char> {
     for x in a.bytes() {
         for y in b.bytes() {
             if x == y {
                 let x = char::from(x);
                 if c.contains(x) {
-                    return x;
+                    return Some(x);
                 }
             }
         }
     }
-    unreachable!()
+    None
 }
 
 /// Calculates the priority of a given character, if it has one.
@@ -73,6 +79,14 @@ pub fn priority(item: char) -> Result<u32, String> {
     }
 }
 
+/// Normalizes the input before the cargo-aoc runner hands it to any of the solvers below.
+#[aoc_generator(day3)]
+#[aoc_generator(day3, part1, Parallel)]
+#[aoc_generator(day3, part2, Parallel)]
+fn generator(input: &str) -> String {
+    normalize_for_day(3, input)
+}
+
 /// Calculates the sum of the priorities of overlapping items in each rucksack.
 /// ```rust
 /// # use aoc_2022::day3::*;
@@ -82,24 +96,21 @@ pub fn priority(item: char) -> Result<u32, String> {
 ///             wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
 ///             ttgJtRGJQctTZtZT\n\
 ///             CrZsJsPPZsGzwwsLwLmpwMDw";
-/// assert_eq!(part1(input), 157);
+/// assert_eq!(part1(input).unwrap(), 157);
 /// ```
 #[aoc(day3, part1)]
-pub fn part1(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|s| {
-            let split_len = s.len() / 2;
-            common_element(&s[0..split_len], &s[split_len..])
-        })
-        .filter_map(|c| {
-            if let Ok(x) = priority(c) {
-                Some(x)
-            } else {
-                None
-            }
-        })
-        .sum()
+pub fn part1(input: &str) -> Result<u32> {
+    let mut sum = 0u32;
+    for s in input.lines() {
+        let (a, b) = s
+            .split_at_checked(s.len() / 2)
+            .ok_or_else(|| anyhow!("Rucksack {s:?} can't be split into two compartments"))?;
+        let c = common_element(a, b)
+            .ok_or_else(|| anyhow!("Rucksack {s:?} has no item in both compartments"))?;
+        sum = add_priority(sum, c)?;
+    }
+
+    Ok(sum)
 }
 
 /// Calculates the sum of the priorities of overlapping items in each rucksack.
@@ -111,10 +122,10 @@ pub fn part1(input: &str) -> u32 {
 ///             wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
 ///             ttgJtRGJQctTZtZT\n\
 ///             CrZsJsPPZsGzwwsLwLmpwMDw";
-/// assert_eq!(part1(input), 157);
+/// assert_eq!(part2(input).unwrap(), 70);
 /// ```
 #[aoc(day3, part2)]
-pub fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> Result<u32> {
     let mut lines_iter = input.lines();
     std::iter::from_fn(|| {
         let a = lines_iter.next()?;
@@ -122,13 +133,45 @@ pub fn part2(input: &str) -> u32 {
         let c = lines_iter.next()?;
         Some([a, b, c])
     })
-    .map(|s| common_element_2(s[0], s[1], s[2]))
-    .filter_map(|c| {
-        if let Ok(x) = priority(c) {
-            Some(x)
-        } else {
-            None
-        }
+    .map(|s| {
+        common_element_2(s[0], s[1], s[2])
+            .ok_or_else(|| anyhow!("Group {s:?} has no badge in common"))
+    })
+    .try_fold(0u32, |sum, c| add_priority(sum, c?))
+}
+
+#[aoc(day3, part1, Parallel)]
+pub fn part1_parallel(input: &str) -> Result<u32> {
+    part1_split(input, chunks::pieces())
+}
+
+#[aoc(day3, part2, Parallel)]
+pub fn part2_parallel(input: &str) -> Result<u32> {
+    part2_split(input, chunks::pieces())
+}
+
+/// Adds the priority of `item` to `sum`, failing if the total doesn't fit.
+fn add_priority(sum: u32, item: char) -> Result<u32> {
+    sum.checked_add(priority(item).unwrap_or_default())
+        .ok_or_else(|| anyhow!("The sum of the priorities is too large"))
+}
+
+/// Adds up the sums for each piece, or fails with the first piece's error or if the total doesn't
+/// fit.
+fn sum_pieces(pieces: Vec<&str>, solve: fn(&str) -> Result<u32>) -> Result<u32> {
+    let sums: Vec<Result<u32>> = pieces.par_iter().map(|piece| solve(piece)).collect();
+    sums.into_iter().try_fold(0u32, |sum, piece| {
+        sum.checked_add(piece?)
+            .ok_or_else(|| anyhow!("The sum of the priorities is too large"))
     })
-    .sum()
+}
+
+/// Solves part 1 on up to `pieces` pieces of the input at once.
+pub(crate) fn part1_split(input: &str, pieces: usize) -> Result<u32> {
+    sum_pieces(chunks::split(input, pieces, "\n"), part1)
+}
+
+/// Solves part 2 on up to `pieces` pieces of the input at once, each holding whole groups.
+pub(crate) fn part2_split(input: &str, pieces: usize) -> Result<u32> {
+    sum_pieces(chunks::split_groups(input, pieces, 3), part2)
 }
@@ -1,6 +1,9 @@
 use anyhow::anyhow;
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{bytes::complete::tag, character::complete, combinator::map, sequence::tuple, IResult};
+use rayon::prelude::*;
+
+use crate::{chunks, normalize::normalize_for_day};
 
 #[derive(Debug)]
 pub struct Range {
@@ -49,6 +52,14 @@ impl Assignment {
     }
 }
 
+/// Normalizes the input before the cargo-aoc runner hands it to any of the solvers below.
+#[aoc_generator(day4)]
+#[aoc_generator(day4, part1, Parallel)]
+#[aoc_generator(day4, part2, Parallel)]
+fn generator(input: &str) -> String {
+    normalize_for_day(4, input)
+}
+
 /// Finds how many elves were assigned containing ranges for work.
 ///
 /// ```rust
@@ -103,6 +114,36 @@ pub fn part2(input: &str) -> anyhow::Result<u32> {
     Ok(count)
 }
 
+#[aoc(day4, part1, Parallel)]
+pub fn part1_parallel(input: &str) -> anyhow::Result<u32> {
+    part1_split(input, chunks::pieces())
+}
+
+#[aoc(day4, part2, Parallel)]
+pub fn part2_parallel(input: &str) -> anyhow::Result<u32> {
+    part2_split(input, chunks::pieces())
+}
+
+/// Adds up the counts for each piece, or fails with the first piece's error or if the total doesn't
+/// fit.
+fn count_pieces(pieces: Vec<&str>, count: fn(&str) -> anyhow::Result<u32>) -> anyhow::Result<u32> {
+    let counts: Vec<anyhow::Result<u32>> = pieces.par_iter().map(|piece| count(piece)).collect();
+    counts.into_iter().try_fold(0u32, |sum, piece| {
+        sum.checked_add(piece?)
+            .ok_or_else(|| anyhow!("Too many pairs to count"))
+    })
+}
+
+/// Solves part 1 on up to `pieces` pieces of the input at once.
+pub(crate) fn part1_split(input: &str, pieces: usize) -> anyhow::Result<u32> {
+    count_pieces(chunks::split(input, pieces, "\n"), part1)
+}
+
+/// Solves part 2 on up to `pieces` pieces of the input at once.
+pub(crate) fn part2_split(input: &str, pieces: usize) -> anyhow::Result<u32> {
+    count_pieces(chunks::split(input, pieces, "\n"), part2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,71 +1,258 @@
-use anyhow::{anyhow, Result, bail};
-use aoc_runner_derive::aoc;
-use nom::{
-    branch::alt,
-    bytes::complete::{tag, take_until},
-    character::complete,
-    combinator::{map, opt},
-    multi::{many0, separated_list1},
-    sequence::{terminated, tuple},
-    IResult,
-};
-
-fn parse_stacks(input: &str) -> IResult<&str, Vec<Vec<char>>> {
-    let (rest, stack_data) = take_until("\n\n")(input)?;
-
-    let num_stacks = if let Some(Some(num_stacks)) = stack_data.lines().last().map(|s| {
-        s.split_whitespace()
-            .last()
-            .map(|s| s.parse::<u32>().unwrap_or_default())
-    }) {
-        dbg!(num_stacks)
-    } else {
-        0
+pub mod animate;
+pub mod history;
+pub mod plan;
+pub mod reverse;
+pub mod runs;
+
+use std::fmt;
+
+use anyhow::{anyhow, bail, Result};
+use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{bytes::complete::tag, character::complete, combinator::map, sequence::tuple, IResult};
+use runs::RunStacks;
+
+use crate::normalize::normalize_for_day;
+
+/// The label on a crate, such as `A` for `[A]` or `A12` for `[A12]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Crate(pub String);
+
+impl Crate {
+    pub fn label(&self) -> &str {
+        &self.0
+    }
+
+    /// Width of the label in characters.
+    fn width(&self) -> usize {
+        self.0.chars().count()
+    }
+}
+
+impl From<char> for Crate {
+    fn from(c: char) -> Self {
+        Crate(c.to_string())
+    }
+}
+
+impl From<&str> for Crate {
+    fn from(s: &str) -> Self {
+        Crate(s.to_string())
+    }
+}
+
+impl PartialEq<char> for Crate {
+    fn eq(&self, other: &char) -> bool {
+        let mut chars = self.0.chars();
+        chars.next() == Some(*other) && chars.next().is_none()
+    }
+}
+
+impl fmt::Display for Crate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Splits a line of the drawing into its non-blank pieces, along with the (0-indexed) column each
+/// one starts at.  Columns are counted in characters rather than bytes.
+fn tokens(line: &str) -> Vec<(usize, String)> {
+    let mut tokens: Vec<(usize, String)> = vec![];
+    let mut previous_blank = true;
+    for (column, c) in line.chars().enumerate() {
+        if c.is_whitespace() {
+            previous_blank = true;
+            continue;
+        }
+        match tokens.last_mut() {
+            Some((_, token)) if !previous_blank => token.push(c),
+            _ => tokens.push((column, c.to_string())),
+        }
+        previous_blank = false;
+    }
+
+    tokens
+}
+
+/// The input split into its two parts, with every line numbered from 1 as in the whole input.
+pub(crate) struct Sections<'a> {
+    /// The lines of the stack drawing, up to the first blank line.
+    pub(crate) drawing: Vec<(usize, &'a str)>,
+    /// The non-blank lines after the drawing, trimmed.
+    pub(crate) commands: Vec<(usize, &'a str)>,
+    /// The first line of each run of blank lines that comes between two commands.
+    pub(crate) gaps: Vec<usize>,
+}
+
+/// Splits the input into the stack drawing and the commands, ignoring any `\r` line endings and
+/// blank lines at the end.
+pub(crate) fn sections(input: &str) -> Sections<'_> {
+    let mut lines = input
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .enumerate()
+        .map(|(i, line)| (i + 1, line));
+
+    let drawing: Vec<_> = lines
+        .by_ref()
+        .take_while(|(_, line)| !line.trim().is_empty())
+        .collect();
+
+    let mut commands = vec![];
+    let mut gaps = vec![];
+    let mut blank = None;
+    for (line_number, line) in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            blank.get_or_insert(line_number);
+            continue;
+        }
+        gaps.extend(blank.take());
+        commands.push((line_number, line));
+    }
+
+    Sections {
+        drawing,
+        commands,
+        gaps,
+    }
+}
+
+/// Parses the drawing of the stacks.  `lines` holds each line of the drawing along with its line
+/// number in the input, ending with the footer that numbers the stacks.
+///
+/// Crates are matched to stacks by column: each crate belongs to the stack whose number sits
+/// underneath it.  This means trailing whitespace can be missing, stack numbers can have more
+/// than one digit and labels can be any width, as long as every crate lines up with exactly one
+/// number.
+fn parse_stacks(lines: &[(usize, &str)]) -> Result<Vec<Vec<Crate>>> {
+    if lines.is_empty() {
+        bail!("Missing stack drawing");
+    }
+
+    check_stacks(lines).map_err(|errors| {
+        let (line, message) = &errors[0];
+        anyhow!("line {line}: {message}")
+    })
+}
+
+/// Does the work of [`parse_stacks`], carrying on past any problems with the drawing so that
+/// every one of them is reported along with its line number.  Nothing can be checked if the
+/// footer is wrong, though.
+pub(crate) fn check_stacks(
+    lines: &[(usize, &str)],
+) -> std::result::Result<Vec<Vec<Crate>>, Vec<(usize, String)>> {
+    let Some(((footer_line, footer), rows)) = lines.split_last() else {
+        return Err(vec![(1, "missing stack drawing".to_string())]);
     };
-    let mut stacks = vec![vec![]; num_stacks as usize];
-    for line in stack_data.lines() {
-        if line.starts_with(" 1") {
-            break;
-        }
-        let data = separated_list1(
-            tag(" "),
-            alt((
-                map(tag("   "), |_| -> Option<char> { None }),
-                map(
-                    tuple((tag("["), complete::anychar, tag("]"))),
-                    |(_, c, _)| -> Option<char> { Some(c) },
+
+    // the columns spanned by each stack's number
+    let mut columns = vec![];
+    for (i, (start, token)) in tokens(footer).into_iter().enumerate() {
+        if token.parse::<usize>().ok() != Some(i + 1) {
+            return Err(vec![(
+                *footer_line,
+                format!(
+                    "expected stack number {} at column {}, found {token:?}",
+                    i + 1,
+                    start + 1
                 ),
-            )),
-        )(line)?;
-        data.1
-            .iter()
-            .zip(stacks.iter_mut())
-            .filter_map(|x| {
-                if let Some(c) = *x.0 {
-                    Some((c, x.1))
-                } else {
-                    None
-                }
-            })
-            .for_each(|(c, stack)| stack.push(c))
+            )]);
+        }
+        columns.push(start..start + token.chars().count());
     }
 
-    // we need to reverse our stacks; the elements we saw first need to come on top of the stack,
-    // not the bottom.
-    stacks.iter_mut().for_each(|v| v.reverse());
+    let mut stacks = vec![vec![]; columns.len()];
+    let mut errors = vec![];
+    // work up from the bottom so that every crate lands on top of the one below it.
+    for (height, (line, row)) in rows.iter().rev().enumerate() {
+        for (start, token) in tokens(row) {
+            let Some(label) = token
+                .strip_prefix('[')
+                .and_then(|t| t.strip_suffix(']'))
+                .filter(|label| !label.is_empty())
+            else {
+                errors.push((
+                    *line,
+                    format!(
+                        "expected a crate like [A] at column {}, found {token:?}",
+                        start + 1
+                    ),
+                ));
+                continue;
+            };
+
+            let end = start + token.chars().count();
+            let mut matching = columns
+                .iter()
+                .enumerate()
+                .filter(|(_, column)| column.start < end && start < column.end);
+            let stack = match (matching.next(), matching.next()) {
+                (Some((stack, _)), None) => stack,
+                (None, _) => {
+                    errors.push((
+                        *line,
+                        format!(
+                            "crate {token} at column {} is not above any stack number",
+                            start + 1
+                        ),
+                    ));
+                    continue;
+                }
+                (Some(_), Some(_)) => {
+                    errors.push((
+                        *line,
+                        format!(
+                            "crate {token} at column {} is above more than one stack number",
+                            start + 1
+                        ),
+                    ));
+                    continue;
+                }
+            };
+
+            if stacks[stack].len() != height {
+                errors.push((
+                    *line,
+                    format!(
+                        "crate {token} at column {} is not sitting on top of stack {}",
+                        start + 1,
+                        stack + 1
+                    ),
+                ));
+                if stacks[stack].len() > height {
+                    continue;
+                }
+                // fill the gap with copies of this crate, so it's only reported once
+                stacks[stack].resize(height, Crate::from(label));
+            }
+            stacks[stack].push(Crate::from(label));
+        }
+    }
 
-    Ok((rest, stacks))
+    if errors.is_empty() {
+        Ok(stacks)
+    } else {
+        Err(errors)
+    }
 }
 
-#[derive(Debug)]
-struct Command {
-    amount: usize,
-    from: usize,
-    to: usize,
+/// A single `move N from A to B` instruction.  Stack numbers are kept exactly as they were
+/// written in the input, i.e. 1-indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Command {
+    pub amount: usize,
+    pub from: usize,
+    pub to: usize,
 }
 
 impl Command {
-    fn parse(input: &str) -> IResult<&str, Self> {
+    /// Parses a single command.
+    /// ```rust
+    /// # use aoc_2022::day5::Command;
+    /// let (_, command) = Command::parse("move 1 from 2 to 1").unwrap();
+    /// assert_eq!(command, Command { amount: 1, from: 2, to: 1 });
+    /// ```
+    pub fn parse(input: &str) -> IResult<&str, Self> {
         map(
             tuple((
                 tag("move "),
@@ -77,86 +264,608 @@ impl Command {
             )),
             |(_, amount, _, from, _, to)| Self {
                 amount: amount as usize,
-                from: from as usize - 1, // input is 1-indexed, we use 0
-                to: to as usize - 1,
+                from: from as usize,
+                to: to as usize,
             },
         )(input)
     }
 }
 
-#[derive(Debug)]
-struct Crane {
-    stacks: Vec<Vec<char>>,
-    commands: Vec<Command>,
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
+    }
+}
+
+/// Which crane is being used to rearrange the crates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CraneModel {
+    /// Moves crates one at a time, reversing their order (part 1).
+    CrateMover9000,
+    /// Moves several crates at once, preserving their order (part 2).
+    CrateMover9001,
+}
+
+/// Why a command could not be carried out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandErrorKind {
+    /// The command tried to take crates from a stack with nothing on it.
+    EmptyStack { stack: usize },
+    /// The command tried to take more crates than the stack holds.
+    InsufficientCrates {
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+    /// The command referred to a stack that doesn't exist.  Stacks are numbered from 1.
+    BadStackIndex { stack: usize },
+}
+
+/// A command that failed validation or simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandError {
+    /// Position of the command within [`Crane::commands`].
+    pub index: usize,
+    /// 1-indexed line of the input the command was read from.
+    pub line: usize,
+    pub kind: CommandErrorKind,
+}
+
+impl fmt::Display for CommandErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CommandErrorKind::EmptyStack { stack } => {
+                write!(f, "attempted to remove a crate from empty stack {stack}")
+            }
+            CommandErrorKind::InsufficientCrates {
+                stack,
+                requested,
+                available,
+            } => write!(
+                f,
+                "attempted to remove {requested} crates from stack {stack}, which only has {available}"
+            ),
+            CommandErrorKind::BadStackIndex { stack } => write!(f, "stack {stack} does not exist"),
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "command {} (line {}): {}",
+            self.index, self.line, self.kind
+        )
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Checks that `command` can be carried out on the given stacks, returning how many crates it
+/// would move.
+fn check_command<T>(
+    stacks: &[T],
+    height: impl Fn(&T) -> usize,
+    command: &Command,
+) -> Result<usize, CommandErrorKind> {
+    for stack in [command.from, command.to] {
+        if stack == 0 || stack > stacks.len() {
+            return Err(CommandErrorKind::BadStackIndex { stack });
+        }
+    }
+
+    let available = height(&stacks[command.from - 1]);
+    if command.amount > 0 && available == 0 {
+        Err(CommandErrorKind::EmptyStack {
+            stack: command.from,
+        })
+    } else if command.amount > available {
+        Err(CommandErrorKind::InsufficientCrates {
+            stack: command.from,
+            requested: command.amount,
+            available,
+        })
+    } else {
+        Ok(command.amount)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Crane {
+    pub stacks: Vec<Vec<Crate>>,
+    pub commands: Vec<Command>,
+    /// Line of the input holding the first command; used for error reporting.
+    first_command_line: usize,
 }
 
 impl Crane {
-    fn parse(input: &str) -> IResult<&str, Crane> {
-        map(
-            tuple((
-                parse_stacks,
-                tag("\n\n"),
-                many0(terminated(Command::parse, opt(complete::newline))),
-            )),
-            |(stacks, _, commands)| Crane { stacks, commands },
-        )(input)
+    /// Creates a crane from stacks listed bottom to top.
+    pub fn new(stacks: Vec<Vec<Crate>>, commands: Vec<Command>) -> Self {
+        Self {
+            stacks,
+            commands,
+            first_command_line: 1,
+        }
     }
 
-    fn process_commands(&mut self) -> anyhow::Result<()> {
-        for command in self.commands.iter() {
-            for _ in 0..command.amount {
-                let from_stack = &mut self.stacks[command.from];
-                let Some(c) = from_stack.pop() else {
-                    bail!("Attempted to remove a crate from empty stack {0}!", command.from);
-                };
-                let to_stack = &mut self.stacks[command.to];
-                to_stack.push(c);
+    /// Parses the stack drawing and the commands following it.  Windows line endings and
+    /// missing trailing whitespace are both accepted.
+    ///
+    /// ```rust
+    /// # use aoc_2022::day5::*;
+    /// let input = "                                    [K]\r\n\
+    ///              [A] [B] [C] [D] [E] [F] [G] [H] [I] [J]\r\n \
+    ///               1   2   3   4   5   6   7   8   9   10\r\n\
+    ///              \r\n\
+    ///              move 2 from 10 to 1\r\n";
+    /// let mut crane = Crane::parse(input).unwrap();
+    /// assert_eq!(crane.stacks.len(), 10);
+    /// crane.process(CraneModel::CrateMover9000).unwrap();
+    /// assert_eq!(crane.read_stack_tops_string(), "JBCDEFGHI");
+    ///
+    /// let misaligned = "  [A]\n 1   2\n\nmove 1 from 1 to 2\n";
+    /// assert_eq!(
+    ///     Crane::parse(misaligned).unwrap_err().to_string(),
+    ///     "line 1: crate [A] at column 3 is not above any stack number"
+    /// );
+    /// ```
+    pub fn parse(input: &str) -> Result<Crane> {
+        let sections = sections(input);
+        let stacks = parse_stacks(&sections.drawing)?;
+
+        let first_command_line = sections.drawing.len() + 2;
+        let mut commands = vec![];
+        for (line_number, line) in sections.commands {
+            if let Some(blank) = sections.gaps.first().filter(|&&blank| blank < line_number) {
+                bail!("line {blank}: unexpected blank line between commands");
             }
+
+            let (_, command) = Command::parse(line).map_err(|e| {
+                anyhow!("line {line_number}: failed to parse command {line:?}: {e}")
+            })?;
+            commands.push(command);
+        }
+
+        Ok(Crane {
+            stacks,
+            commands,
+            first_command_line,
+        })
+    }
+
+    fn command_error(&self, index: usize, kind: CommandErrorKind) -> CommandError {
+        CommandError {
+            index,
+            line: self.first_command_line + index,
+            kind,
+        }
+    }
+
+    /// Checks every command against the stacks without moving any crates, reporting all of the
+    /// commands that would fail.  Both crane models move the same number of crates per command,
+    /// so the result holds for either of them.
+    ///
+    /// ```rust
+    /// # use aoc_2022::day5::*;
+    /// let input = "    [D]    \n\
+    ///              [N] [C]    \n\
+    ///              [Z] [M] [P]\n \
+    ///               1   2   3 \n\
+    ///              \n\
+    ///              move 1 from 2 to 1\n\
+    ///              move 5 from 1 to 3\n\
+    ///              move 1 from 4 to 1\n";
+    /// let crane = Crane::parse(input).unwrap();
+    /// let errors = crane.validate().unwrap_err();
+    /// assert_eq!(
+    ///     errors,
+    ///     vec![
+    ///         CommandError {
+    ///             index: 1,
+    ///             line: 7,
+    ///             kind: CommandErrorKind::InsufficientCrates { stack: 1, requested: 5, available: 3 },
+    ///         },
+    ///         CommandError {
+    ///             index: 2,
+    ///             line: 8,
+    ///             kind: CommandErrorKind::BadStackIndex { stack: 4 },
+    ///         },
+    ///     ]
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<CommandError>> {
+        let mut heights: Vec<usize> = self.stacks.iter().map(Vec::len).collect();
+        let mut errors = vec![];
+        for (index, command) in self.commands.iter().enumerate() {
+            let moved = match check_command(&heights, |&h| h, command) {
+                Ok(moved) => moved,
+                Err(kind) => {
+                    errors.push(self.command_error(index, kind));
+                    match kind {
+                        // keep going with whatever is left on the stack so later commands are
+                        // still checked against something sensible.
+                        CommandErrorKind::EmptyStack { .. } => 0,
+                        CommandErrorKind::InsufficientCrates { available, .. } => available,
+                        CommandErrorKind::BadStackIndex { .. } => continue,
+                    }
+                }
+            };
+            heights[command.from - 1] -= moved;
+            heights[command.to - 1] += moved;
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Runs every command using the given crane model, stopping at the first one that can't be
+    /// carried out.
+    ///
+    /// ```rust
+    /// # use aoc_2022::day5::*;
+    /// let input = "[A]    \n[B] [C]\n 1   2 \n\nmove 3 from 1 to 2\n";
+    /// for model in [CraneModel::CrateMover9000, CraneModel::CrateMover9001] {
+    ///     let mut crane = Crane::parse(input).unwrap();
+    ///     let error = crane.process(model).unwrap_err();
+    ///     assert_eq!(
+    ///         error.kind,
+    ///         CommandErrorKind::InsufficientCrates { stack: 1, requested: 3, available: 2 }
+    ///     );
+    ///     assert_eq!(crane.stacks, vec![vec!['B', 'A'], vec!['C']]);
+    /// }
+    /// ```
+    pub fn process(&mut self, model: CraneModel) -> Result<(), CommandError> {
+        for index in 0..self.commands.len() {
+            self.apply(index, model)?;
         }
 
         Ok(())
     }
 
-    fn process_commands_2(&mut self) -> anyhow::Result<()> {
-        for command in self.commands.iter() {
-            let from_stack = &mut self.stacks[command.from];
-            let to_remove = from_stack.len().saturating_sub(command.amount);
-            let data: Vec<_> = from_stack.drain(to_remove..).collect();
-            let to_stack = &mut self.stacks[command.to];
-            to_stack.extend_from_slice(&data);
+    /// Carries out the command at `index` using the given crane model.  The stacks are left
+    /// untouched if the command can't be carried out.
+    pub fn apply(&mut self, index: usize, model: CraneModel) -> Result<(), CommandError> {
+        let command = self.commands[index];
+        check_command(&self.stacks, Vec::len, &command)
+            .map_err(|kind| self.command_error(index, kind))?;
+        move_crates(&mut self.stacks, &command, model);
+
+        Ok(())
+    }
+
+    /// The original implementation of [`Crane::process`], which moves crates one at a time by
+    /// popping and pushing for the CrateMover 9000 and moves them through a temporary `Vec` for
+    /// the 9001.  Kept around to benchmark against.
+    pub fn process_original(&mut self, model: CraneModel) -> Result<(), CommandError> {
+        for (index, command) in self.commands.iter().enumerate() {
+            check_command(&self.stacks, Vec::len, command)
+                .map_err(|kind| self.command_error(index, kind))?;
+            let (from, to) = (command.from - 1, command.to - 1);
+            match model {
+                CraneModel::CrateMover9000 => {
+                    for _ in 0..command.amount {
+                        let c = self.stacks[from].pop().expect("the command was checked");
+                        self.stacks[to].push(c);
+                    }
+                }
+                CraneModel::CrateMover9001 => {
+                    let from_stack = &mut self.stacks[from];
+                    let to_remove = from_stack.len() - command.amount;
+                    let data: Vec<_> = from_stack.drain(to_remove..).collect();
+                    self.stacks[to].extend(data);
+                }
+            }
         }
 
         Ok(())
     }
 
-    fn read_stack_tops(&self) -> String {
-        let mut s = String::new();
-        for stack in self.stacks.iter() {
-            if let Some(c) = stack.last() {
-                s.push(*c);
+    /// Does the same as [`Crane::process`] using [`RunStacks`], which takes time logarithmic in
+    /// the number of commands for each one rather than linear in the number of crates it moves.
+    ///
+    /// ```rust
+    /// # use aoc_2022::day5::*;
+    /// let input = "    [D]    \n\
+    ///              [N] [C]    \n\
+    ///              [Z] [M] [P]\n \
+    ///               1   2   3 \n\
+    ///              \n\
+    ///              move 1 from 2 to 1\n\
+    ///              move 3 from 1 to 3\n\
+    ///              move 2 from 2 to 1\n\
+    ///              move 1 from 1 to 2\n";
+    /// let mut crane = Crane::parse(input).unwrap();
+    /// crane.process_runs(CraneModel::CrateMover9001).unwrap();
+    /// assert_eq!(crane.read_stack_tops_string(), "MCD");
+    /// ```
+    pub fn process_runs(&mut self, model: CraneModel) -> Result<(), CommandError> {
+        let mut stacks = RunStacks::new(std::mem::take(&mut self.stacks));
+        let mut result = Ok(());
+        for (index, command) in self.commands.iter().enumerate() {
+            if let Err(kind) = stacks.apply(command, model) {
+                result = Err(self.command_error(index, kind));
+                break;
+            }
+        }
+        self.stacks = stacks.into_stacks();
+
+        result
+    }
+
+    fn process_commands(&mut self) -> anyhow::Result<()> {
+        Ok(self.process_runs(CraneModel::CrateMover9000)?)
+    }
+
+    fn process_commands_2(&mut self) -> anyhow::Result<()> {
+        Ok(self.process_runs(CraneModel::CrateMover9001)?)
+    }
+
+    /// Collects the crate on top of each stack, skipping empty stacks.
+    ///
+    /// ```rust
+    /// # use aoc_2022::day5::*;
+    /// let input = "[A12]      \n[B]   [C7]\n  1     2  \n\nmove 1 from 1 to 2\n";
+    /// let mut crane = Crane::parse(input).unwrap();
+    /// crane.process(CraneModel::CrateMover9000).unwrap();
+    /// assert_eq!(crane.read_stack_tops(), vec![&Crate::from("B"), &Crate::from("A12")]);
+    /// assert_eq!(crane.read_stack_tops_string(), "BA12");
+    /// ```
+    pub fn read_stack_tops(&self) -> Vec<&Crate> {
+        self.stacks
+            .iter()
+            .filter_map(|stack| stack.last())
+            .collect()
+    }
+
+    /// The labels of the crates on top of each stack joined together, which is the puzzle's answer
+    /// when every label is a single character.
+    pub fn read_stack_tops_string(&self) -> String {
+        self.read_stack_tops()
+            .into_iter()
+            .map(Crate::label)
+            .collect()
+    }
+
+    /// Draws the stacks in the same format as the puzzle input, footer included.
+    ///
+    /// ```rust
+    /// # use aoc_2022::day5::*;
+    /// let input = "    [D]    \n\
+    ///              [N] [C]    \n\
+    ///              [Z] [M] [P]\n \
+    ///               1   2   3 \n\
+    ///              \n\
+    ///              move 1 from 2 to 1\n";
+    /// let mut crane = Crane::parse(input).unwrap();
+    /// crane.process(CraneModel::CrateMover9000).unwrap();
+    /// assert_eq!(
+    ///     crane.render(),
+    ///     "[D]        \n\
+    ///      [N] [C]    \n\
+    ///      [Z] [M] [P]\n \
+    ///       1   2   3 "
+    /// );
+    /// ```
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Moves crates between stacks without any checks; the command must already have been validated
+/// with [`check_command`].
+fn move_crates<T>(stacks: &mut [Vec<T>], command: &Command, model: CraneModel) {
+    let (from, to) = (command.from - 1, command.to - 1);
+    let split = stacks[from].len() - command.amount;
+    if from == to {
+        // the crates land back where they were, so only the CrateMover 9000's reversal shows
+        if model == CraneModel::CrateMover9000 {
+            stacks[from][split..].reverse();
+        }
+        return;
+    }
+
+    // borrow both stacks at once so the crates go straight from one to the other
+    let (from_stack, to_stack) = if from < to {
+        let (low, high) = stacks.split_at_mut(to);
+        (&mut low[from], &mut high[0])
+    } else {
+        let (low, high) = stacks.split_at_mut(from);
+        (&mut high[0], &mut low[to])
+    };
+    let moved = from_stack.drain(split..);
+    match model {
+        CraneModel::CrateMover9000 => to_stack.extend(moved.rev()),
+        CraneModel::CrateMover9001 => to_stack.extend(moved),
+    }
+}
+
+impl fmt::Display for Crane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        draw_stacks(f, &self.stacks, |f, _, _, c| write!(f, "[{c}]"))
+    }
+}
+
+/// Draws `stacks` in the puzzle's format, calling `draw_crate` with the stack index, height and
+/// label of each crate to fill in its cell.  Every cell is as wide as the widest label, with
+/// shorter crates padded on the right.
+fn draw_stacks<F>(f: &mut fmt::Formatter<'_>, stacks: &[Vec<Crate>], draw_crate: F) -> fmt::Result
+where
+    F: Fn(&mut fmt::Formatter<'_>, usize, usize, &Crate) -> fmt::Result,
+{
+    let width = stacks.iter().flatten().map(Crate::width).max().unwrap_or(1);
+    let height = stacks.iter().map(Vec::len).max().unwrap_or_default();
+    for row in (0..height).rev() {
+        for (i, stack) in stacks.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            match stack.get(row) {
+                Some(c) => {
+                    draw_crate(f, i, row, c)?;
+                    write!(f, "{:1$}", "", width - c.width())?;
+                }
+                None => write!(f, "{:1$}", "", width + 2)?,
             }
         }
+        writeln!(f)?;
+    }
 
-        s
+    // numbers are left-aligned under the crates so that double-digit ones still line up
+    for i in 0..stacks.len() {
+        if i > 0 {
+            f.write_str(" ")?;
+        }
+        write!(f, " {:<1$}", i + 1, width + 1)?;
     }
+
+    Ok(())
+}
+
+/// Normalizes the input before the cargo-aoc runner hands it to any of the solvers below.
+#[aoc_generator(day5)]
+#[aoc_generator(day5, part1, Vec)]
+#[aoc_generator(day5, part2, Vec)]
+fn generator(input: &str) -> String {
+    normalize_for_day(5, input)
 }
 
+/// Finds the crates on top of each stack after the CrateMover 9000 has run every command.
+///
+/// ```rust
+/// # use aoc_2022::day5::*;
+/// let input = "    [D]    \n\
+///              [N] [C]    \n\
+///              [Z] [M] [P]\n \
+///               1   2   3 \n\
+///              \n\
+///              move 1 from 2 to 1\n\
+///              move 3 from 1 to 3\n\
+///              move 2 from 2 to 1\n\
+///              move 1 from 1 to 2";
+/// assert_eq!(part1(input).unwrap(), "CMZ");
+/// ```
 #[aoc(day5, part1)]
-fn part1(input: &str) -> Result<String> {
-    let mut crane = Crane::parse(input)
-        .map(|x| x.1)
-        .map_err(|e| anyhow!("Failed to parse input: {e}"))?;
+pub fn part1(input: &str) -> Result<String> {
+    let mut crane = Crane::parse(input)?;
 
     crane.process_commands()?;
-    Ok(crane.read_stack_tops())
+    Ok(crane.read_stack_tops_string())
 }
 
+/// Finds the crates on top of each stack after the CrateMover 9001 has run every command.
+///
+/// ```rust
+/// # use aoc_2022::day5::*;
+/// let input = "    [D]    \n\
+///              [N] [C]    \n\
+///              [Z] [M] [P]\n \
+///               1   2   3 \n\
+///              \n\
+///              move 1 from 2 to 1\n\
+///              move 3 from 1 to 3\n\
+///              move 2 from 2 to 1\n\
+///              move 1 from 1 to 2";
+/// assert_eq!(part2(input).unwrap(), "MCD");
+/// ```
 #[aoc(day5, part2)]
-fn part2(input: &str) -> Result<String> {
-    let mut crane = Crane::parse(input)
-        .map(|x| x.1)
-        .map_err(|e| anyhow!("Failed to parse input: {e}"))?;
+pub fn part2(input: &str) -> Result<String> {
+    let mut crane = Crane::parse(input)?;
 
     crane.process_commands_2()?;
-    Ok(crane.read_stack_tops())
+    Ok(crane.read_stack_tops_string())
+}
+
+#[aoc(day5, part1, Vec)]
+fn part1_vec(input: &str) -> Result<String> {
+    let mut crane = Crane::parse(input)?;
+
+    crane.process(CraneModel::CrateMover9000)?;
+    Ok(crane.read_stack_tops_string())
+}
+
+#[aoc(day5, part2, Vec)]
+fn part2_vec(input: &str) -> Result<String> {
+    let mut crane = Crane::parse(input)?;
+
+    crane.process(CraneModel::CrateMover9001)?;
+    Ok(crane.read_stack_tops_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_irregular_whitespace() {
+        let input = include_str!("../input/2022/day5.txt");
+        let expected = Crane::parse(input).unwrap();
+
+        let trimmed: String = input
+            .lines()
+            .map(|l| format!("{}\n", l.trim_end()))
+            .collect();
+        let crlf = input.replace('\n', "\r\n");
+        for edited in [trimmed, crlf] {
+            let crane = Crane::parse(&edited).unwrap();
+            assert_eq!(crane.stacks, expected.stacks);
+            assert_eq!(crane.commands, expected.commands);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn render_round_trips(
+            stacks in prop::collection::vec(
+                prop::collection::vec("[!-~]{1,4}".prop_map(Crate), 0..8),
+                1..20,
+            )
+        ) {
+            let crane = Crane::new(stacks, vec![]);
+            let parsed = Crane::parse(&crane.render()).unwrap();
+            prop_assert_eq!(parsed.stacks, crane.stacks);
+        }
+
+        #[test]
+        fn runs_match_simulation(
+            stacks in prop::collection::vec(
+                prop::collection::vec("[A-Z]".prop_map(Crate), 0..30),
+                1..6,
+            ),
+            moves in prop::collection::vec((0..100usize, 0..6usize, 0..6usize), 0..200),
+        ) {
+            // turn the random moves into commands that are all valid for these stacks
+            let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+            let mut commands = vec![];
+            for (amount, from, to) in moves {
+                let (from, to) = (from % heights.len(), to % heights.len());
+                let amount = amount % (heights[from] + 1);
+                heights[from] -= amount;
+                heights[to] += amount;
+                commands.push(Command { amount, from: from + 1, to: to + 1 });
+            }
+
+            for model in [CraneModel::CrateMover9000, CraneModel::CrateMover9001] {
+                let mut expected = Crane::new(stacks.clone(), commands.clone());
+                expected.process(model).unwrap();
+                let mut actual = Crane::new(stacks.clone(), commands.clone());
+                actual.process_runs(model).unwrap();
+                prop_assert_eq!(&actual.stacks, &expected.stacks);
+
+                let mut runs = RunStacks::new(stacks.clone());
+                for command in &commands {
+                    runs.apply(command, model).unwrap();
+                }
+                let heights: Vec<usize> = expected.stacks.iter().map(Vec::len).collect();
+                prop_assert_eq!(runs.heights(), &heights[..]);
+                for (i, stack) in expected.stacks.iter().enumerate() {
+                    prop_assert_eq!(runs.top(i), stack.last());
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,246 @@
+//! Replays a [`Crane`]'s commands one at a time so the rearrangement can be watched.
+
+use std::{fmt, io::Write};
+
+use super::{draw_stacks, Command, CommandError, Crane, CraneModel, Crate};
+
+/// How crates that were just moved should stand out in a rendered frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Moved crates are drawn as `<A>` instead of `[A]`, for writing frames to a file.
+    Plain,
+    /// Moved crates are drawn in reverse video, for a terminal.
+    Ansi,
+}
+
+/// The state of the stacks after a single command.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// Index of the command that produced this frame, or `None` for the initial state.
+    pub index: Option<usize>,
+    /// Number of commands in the whole animation.
+    pub total: usize,
+    pub command: Option<Command>,
+    pub stacks: Vec<Vec<Crate>>,
+}
+
+impl Frame {
+    /// Was the crate at `height` in the 0-indexed `stack` moved by this frame's command?
+    pub fn is_moved(&self, stack: usize, height: usize) -> bool {
+        match self.command {
+            Some(command) if command.to - 1 == stack => {
+                height + command.amount >= self.stacks[stack].len()
+            }
+            _ => false,
+        }
+    }
+
+    /// Renders the frame as a heading describing the command followed by the stack drawing.
+    ///
+    /// ```rust
+    /// # use aoc_2022::day5::{Crane, CraneModel, animate::*};
+    /// let input = "[A]    \n[B] [C]\n 1   2 \n\nmove 1 from 1 to 2\n";
+    /// let crane = Crane::parse(input).unwrap();
+    /// let frames: Vec<_> = Animation::new(crane, CraneModel::CrateMover9000)
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(frames.len(), 2);
+    /// assert_eq!(
+    ///     frames[1].render(Style::Plain),
+    ///     "command 1 of 1: move 1 from 1 to 2\n    <A>\n[B] [C]\n 1   2 "
+    /// );
+    /// ```
+    pub fn render(&self, style: Style) -> String {
+        Rendered { frame: self, style }.to_string()
+    }
+}
+
+struct Rendered<'a> {
+    frame: &'a Frame,
+    style: Style,
+}
+
+impl fmt::Display for Rendered<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frame = self.frame;
+        match frame.command.zip(frame.index) {
+            Some((command, index)) => {
+                writeln!(f, "command {} of {}: {command}", index + 1, frame.total)?
+            }
+            None => writeln!(f, "initial state")?,
+        }
+
+        draw_stacks(f, &frame.stacks, |f, stack, height, c| {
+            if !frame.is_moved(stack, height) {
+                write!(f, "[{c}]")
+            } else if self.style == Style::Ansi {
+                write!(f, "\x1b[7m[{c}]\x1b[0m")
+            } else {
+                write!(f, "<{c}>")
+            }
+        })
+    }
+}
+
+/// An iterator over the frames of a crane's rearrangement, starting with its initial state.  If a
+/// command fails, its error is yielded and the animation ends.
+pub struct Animation {
+    crane: Crane,
+    model: CraneModel,
+    next: Option<usize>,
+}
+
+impl Animation {
+    pub fn new(crane: Crane, model: CraneModel) -> Self {
+        Self {
+            crane,
+            model,
+            next: None,
+        }
+    }
+
+    /// Total number of commands the animation will replay.
+    pub fn len(&self) -> usize {
+        self.crane.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.crane.commands.is_empty()
+    }
+}
+
+impl Iterator for Animation {
+    type Item = Result<Frame, CommandError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = match self.next {
+            None => {
+                self.next = Some(0);
+                return Some(Ok(Frame {
+                    index: None,
+                    total: self.len(),
+                    command: None,
+                    stacks: self.crane.stacks.clone(),
+                }));
+            }
+            Some(index) if index < self.crane.commands.len() => index,
+            Some(_) => return None,
+        };
+
+        if let Err(e) = self.crane.apply(index, self.model) {
+            self.next = Some(usize::MAX);
+            return Some(Err(e));
+        }
+        self.next = Some(index + 1);
+
+        Some(Ok(Frame {
+            index: Some(index),
+            total: self.len(),
+            command: Some(self.crane.commands[index]),
+            stacks: self.crane.stacks.clone(),
+        }))
+    }
+}
+
+/// Writes every frame of `animation` to `out` in the plain style as soon as it's rendered, each
+/// followed by a blank line.  If a command fails, the frames leading up to it are still written
+/// before its error is returned, so they can be looked at.
+pub fn write_frames(animation: Animation, mut out: impl Write) -> anyhow::Result<()> {
+    for frame in animation {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                out.flush()?;
+                return Err(e.into());
+            }
+        };
+        let rendered = Rendered {
+            frame: &frame,
+            style: Style::Plain,
+        };
+        write!(out, "{rendered}\n\n")?;
+    }
+    out.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str =
+        "    [C]\n[A] [B]\n 1   2 \n\nmove 2 from 2 to 1\nmove 3 from 2 to 1\nmove 1 from 1 to 2\n";
+
+    #[test]
+    fn test_stops_after_error() {
+        let crane = Crane::parse(INPUT).unwrap();
+        let mut animation = Animation::new(crane, CraneModel::CrateMover9000);
+        assert_eq!(animation.len(), 3);
+        assert_eq!(animation.next().unwrap().unwrap().index, None);
+        let frame = animation.next().unwrap().unwrap();
+        assert_eq!((frame.index, frame.total), (Some(0), 3));
+        assert_eq!(animation.next().unwrap().unwrap_err().index, 1);
+        assert!(animation.next().is_none());
+        assert!(animation.next().is_none());
+    }
+
+    #[test]
+    fn test_highlights_moved_crates() {
+        let cases = [
+            (
+                "move 2 from 2 to 1",
+                CraneModel::CrateMover9000,
+                "command 1 of 1: move 2 from 2 to 1\n<B>    \n<C>    \n[A]    \n 1   2 ",
+            ),
+            (
+                "move 2 from 2 to 1",
+                CraneModel::CrateMover9001,
+                "command 1 of 1: move 2 from 2 to 1\n<C>    \n<B>    \n[A]    \n 1   2 ",
+            ),
+            (
+                "move 2 from 2 to 2",
+                CraneModel::CrateMover9000,
+                "command 1 of 1: move 2 from 2 to 2\n    <B>\n[A] <C>\n 1   2 ",
+            ),
+            (
+                "move 2 from 2 to 2",
+                CraneModel::CrateMover9001,
+                "command 1 of 1: move 2 from 2 to 2\n    <C>\n[A] <B>\n 1   2 ",
+            ),
+        ];
+        for (command, model, expected) in cases {
+            let input = format!("    [C]\n[A] [B]\n 1   2 \n\n{command}\n");
+            let frames: Vec<Frame> = Animation::new(Crane::parse(&input).unwrap(), model)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(
+                frames[1].render(Style::Plain),
+                expected,
+                "{command}, {model:?}"
+            );
+            assert!(!frames[0].render(Style::Plain).contains('<'));
+
+            let ansi = frames[1].render(Style::Ansi);
+            assert_eq!(ansi.matches("\x1b[7m").count(), 2, "{command}, {model:?}");
+            assert!(!ansi.contains('<'));
+        }
+    }
+
+    #[test]
+    fn test_write_frames_keeps_frames_before_error() {
+        let crane = Crane::parse(INPUT).unwrap();
+        let expected: String = Animation::new(crane.clone(), CraneModel::CrateMover9000)
+            .take(2)
+            .map(|frame| frame.unwrap().render(Style::Plain) + "\n\n")
+            .collect();
+
+        let mut out = vec![];
+        let error = write_frames(Animation::new(crane, CraneModel::CrateMover9000), &mut out)
+            .unwrap_err()
+            .downcast::<CommandError>()
+            .unwrap();
+        assert_eq!(error.index, 1);
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+}
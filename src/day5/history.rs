@@ -0,0 +1,186 @@
+//! Undo, redo and random access over a crane's commands.
+
+use super::{move_crates, Command, CommandError, Crane, CraneModel, Crate};
+
+/// A command carried out by a particular crane model.  Every operation can be undone by moving the
+/// same number of crates back with the same model: the CrateMover 9000 reverses the crates a
+/// second time, and the CrateMover 9001 keeps their order both ways.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    pub command: Command,
+    pub model: CraneModel,
+}
+
+impl Operation {
+    /// The operation that puts the crates back where they came from.
+    pub fn inverse(&self) -> Self {
+        Self {
+            command: Command {
+                amount: self.command.amount,
+                from: self.command.to,
+                to: self.command.from,
+            },
+            model: self.model,
+        }
+    }
+}
+
+/// Tracks how far through its commands a crane is, allowing moving backwards and forwards through
+/// them.  A snapshot of the stacks is kept every `checkpoint_interval` commands, so seeking to any
+/// point that has been reached before never replays more than that many commands.
+#[derive(Debug, Clone)]
+pub struct History {
+    crane: Crane,
+    model: CraneModel,
+    position: usize,
+    checkpoint_interval: usize,
+    /// `checkpoints[i]` holds the stacks after `i * checkpoint_interval` commands.
+    checkpoints: Vec<Vec<Vec<Crate>>>,
+}
+
+impl History {
+    /// Starts a history at the crane's current state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `checkpoint_interval` is 0.
+    pub fn new(crane: Crane, model: CraneModel, checkpoint_interval: usize) -> Self {
+        assert!(
+            checkpoint_interval > 0,
+            "checkpoint interval must be positive"
+        );
+        Self {
+            checkpoints: vec![crane.stacks.clone()],
+            crane,
+            model,
+            position: 0,
+            checkpoint_interval,
+        }
+    }
+
+    /// Number of commands that have been applied.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Total number of commands.
+    pub fn len(&self) -> usize {
+        self.crane.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.crane.commands.is_empty()
+    }
+
+    /// The crane as it stands after [`History::position`] commands.
+    pub fn crane(&self) -> &Crane {
+        &self.crane
+    }
+
+    /// The operation for the command at `index`.
+    pub fn operation(&self, index: usize) -> Operation {
+        Operation {
+            command: self.crane.commands[index],
+            model: self.model,
+        }
+    }
+
+    /// Reverts the most recently applied command.  Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+
+        self.position -= 1;
+        let inverse = self.operation(self.position).inverse();
+        move_crates(&mut self.crane.stacks, &inverse.command, inverse.model);
+        true
+    }
+
+    /// Applies the next command.  Returns `false` if every command has already been applied.
+    pub fn redo(&mut self) -> Result<bool, CommandError> {
+        if self.position == self.len() {
+            return Ok(false);
+        }
+
+        self.crane.apply(self.position, self.model)?;
+        self.position += 1;
+        if self.position.is_multiple_of(self.checkpoint_interval)
+            && self.checkpoints.len() == self.position / self.checkpoint_interval
+        {
+            self.checkpoints.push(self.crane.stacks.clone());
+        }
+
+        Ok(true)
+    }
+
+    /// Moves to the state after the first `n` commands, stopping at the last command if `n` is
+    /// past the end.  If a command fails along the way, the history stays just before it.
+    ///
+    /// ```rust
+    /// # use aoc_2022::day5::{Crane, CraneModel, history::History};
+    /// let input = "    [D]    \n\
+    ///              [N] [C]    \n\
+    ///              [Z] [M] [P]\n \
+    ///               1   2   3 \n\
+    ///              \n\
+    ///              move 1 from 2 to 1\n\
+    ///              move 3 from 1 to 3\n\
+    ///              move 2 from 2 to 1\n\
+    ///              move 1 from 1 to 2\n";
+    /// let crane = Crane::parse(input).unwrap();
+    /// let mut history = History::new(crane, CraneModel::CrateMover9000, 2);
+    /// history.seek(4).unwrap();
+    /// assert_eq!(history.crane().read_stack_tops_string(), "CMZ");
+    /// history.seek(1).unwrap();
+    /// assert_eq!(history.crane().read_stack_tops_string(), "DCP");
+    /// assert!(history.undo());
+    /// assert_eq!(history.crane().read_stack_tops_string(), "NDP");
+    /// assert!(history.redo().unwrap());
+    /// assert_eq!(history.position(), 1);
+    /// ```
+    pub fn seek(&mut self, n: usize) -> Result<(), CommandError> {
+        let n = n.min(self.len());
+        let interval = self.checkpoint_interval;
+        if n < self.position && self.position - n <= interval {
+            while self.position > n {
+                self.undo();
+            }
+            return Ok(());
+        }
+
+        let checkpoint = (n / interval).min(self.checkpoints.len() - 1);
+        if n < self.position || checkpoint * interval > self.position {
+            self.crane.stacks = self.checkpoints[checkpoint].clone();
+            self.position = checkpoint * interval;
+        }
+        while self.position < n {
+            self.redo()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seek_matches_simulation() {
+        let input = include_str!("../../input/2022/day5.txt");
+        let crane = Crane::parse(input).unwrap();
+        for model in [CraneModel::CrateMover9000, CraneModel::CrateMover9001] {
+            let mut history = History::new(crane.clone(), model, 16);
+            for n in [300, 17, 503, 0, 250, 251, 240, 16, 504, 1000] {
+                let mut expected = crane.clone();
+                for index in 0..n.min(crane.commands.len()) {
+                    expected.apply(index, model).unwrap();
+                }
+
+                history.seek(n).unwrap();
+                assert_eq!(history.crane().stacks, expected.stacks, "seek({n})");
+            }
+        }
+    }
+}
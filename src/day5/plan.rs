@@ -0,0 +1,238 @@
+//! Finds the shortest list of commands that rearranges one set of stacks into another.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt,
+};
+
+use super::{move_crates, Command, CraneModel, Crate};
+
+/// Why no plan could be found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanError {
+    /// The initial and target stacks have a different number of stacks.
+    StackCountMismatch { initial: usize, target: usize },
+    /// The initial and target stacks don't hold the same crates.
+    DifferentCrates,
+    /// The target can't be reached, or not within the length limit.
+    Unreachable,
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::StackCountMismatch { initial, target } => write!(
+                f,
+                "started with {initial} stacks but the target has {target} stacks"
+            ),
+            PlanError::DifferentCrates => {
+                write!(
+                    f,
+                    "the target stacks hold different crates to the initial ones"
+                )
+            }
+            PlanError::Unreachable => write!(f, "the target stacks can't be reached"),
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+type State = Vec<Vec<u32>>;
+
+/// A state reached during the search.
+struct Node {
+    state: State,
+    /// Fewest commands known to reach this state.
+    distance: usize,
+    /// The previous state and the command that led from it.
+    parent: Option<(usize, Command)>,
+}
+
+/// A lower bound on the number of commands still needed.  Every stack that has a crate out of
+/// place needs at least one command to take crates off it, and every stack that is missing crates
+/// needs at least one to put crates on it; a single command can only fix one of each.
+fn estimate(state: &State, target: &State) -> usize {
+    let (mut to_clear, mut to_fill) = (0, 0);
+    for (stack, wanted) in state.iter().zip(target) {
+        let settled = stack.iter().zip(wanted).take_while(|(a, b)| a == b).count();
+        to_clear += usize::from(stack.len() > settled);
+        to_fill += usize::from(wanted.len() > settled);
+    }
+
+    to_clear.max(to_fill)
+}
+
+/// Replaces each crate with a number, giving crates with the same label the same number.
+fn number<'a>(ids: &mut HashMap<&'a Crate, u32>, stacks: &'a [Vec<Crate>]) -> State {
+    stacks
+        .iter()
+        .map(|stack| {
+            stack
+                .iter()
+                .map(|c| {
+                    let next = ids.len() as u32;
+                    *ids.entry(c).or_insert(next)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Searches for a shortest list of commands that turns `initial` into `target` using the given
+/// crane model, optionally giving up on plans longer than `max_len`.  Both sets of stacks are
+/// listed bottom to top.  The search is A*, using [`estimate`] as its heuristic.
+///
+/// ```rust
+/// # use aoc_2022::day5::{*, plan::*};
+/// let stacks = |s: &[&str]| -> Vec<Vec<Crate>> {
+///     s.iter().map(|s| s.chars().map(Crate::from).collect()).collect()
+/// };
+/// let initial = stacks(&["ZN", "MCD", "P"]);
+/// let target = stacks(&["C", "M", "PDNZ"]);
+///
+/// let commands = plan(&initial, &target, CraneModel::CrateMover9000, None).unwrap();
+/// let text: Vec<_> = commands.iter().map(ToString::to_string).collect();
+/// // one fewer than the puzzle's own example needs
+/// assert_eq!(text, ["move 1 from 2 to 1", "move 3 from 1 to 3", "move 1 from 2 to 1"]);
+///
+/// let mut crane = Crane::new(initial.clone(), commands);
+/// crane.process(CraneModel::CrateMover9000).unwrap();
+/// assert_eq!(crane.stacks, target);
+///
+/// assert_eq!(
+///     plan(&initial, &target, CraneModel::CrateMover9000, Some(1)),
+///     Err(PlanError::Unreachable)
+/// );
+/// ```
+pub fn plan(
+    initial: &[Vec<Crate>],
+    target: &[Vec<Crate>],
+    model: CraneModel,
+    max_len: Option<usize>,
+) -> Result<Vec<Command>, PlanError> {
+    if initial.len() != target.len() {
+        return Err(PlanError::StackCountMismatch {
+            initial: initial.len(),
+            target: target.len(),
+        });
+    }
+
+    // search over numbered crates; crates with the same label are interchangeable.
+    let mut ids = HashMap::new();
+    let start = number(&mut ids, initial);
+    let goal = number(&mut ids, target);
+
+    let sorted = |state: &State| {
+        let mut crates: Vec<u32> = state.iter().flatten().copied().collect();
+        crates.sort_unstable();
+        crates
+    };
+    if sorted(&start) != sorted(&goal) {
+        return Err(PlanError::DifferentCrates);
+    }
+
+    let max_len = max_len.unwrap_or(usize::MAX);
+    let mut nodes = vec![Node {
+        state: start.clone(),
+        distance: 0,
+        parent: None,
+    }];
+    let mut seen: HashMap<State, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut queue = BinaryHeap::from([Reverse((estimate(&start, &goal), 0, 0))]);
+
+    while let Some(Reverse((_, distance, id))) = queue.pop() {
+        if distance > nodes[id].distance {
+            // a shorter route here was found after this entry was queued
+            continue;
+        }
+        if nodes[id].state == goal {
+            let mut commands = vec![];
+            let mut current = id;
+            while let Some((previous, command)) = nodes[current].parent {
+                commands.push(command);
+                current = previous;
+            }
+            commands.reverse();
+            return Ok(commands);
+        }
+
+        let state = nodes[id].state.clone();
+        for from in 0..state.len() {
+            // moving crates back onto their own stack reverses them with the CrateMover 9000, but
+            // does nothing with the 9001, and neither does moving a single crate
+            let to_self = model == CraneModel::CrateMover9000;
+            for to in (0..state.len()).filter(|&to| to != from || to_self) {
+                let least = if to == from { 2 } else { 1 };
+                for amount in least..=state[from].len() {
+                    let command = Command {
+                        amount,
+                        from: from + 1,
+                        to: to + 1,
+                    };
+                    let mut next = state.clone();
+                    move_crates(&mut next, &command, model);
+
+                    let cost = distance + 1 + estimate(&next, &goal);
+                    if cost > max_len {
+                        continue;
+                    }
+                    let next_id = match seen.get(&next) {
+                        Some(&next_id) if nodes[next_id].distance <= distance + 1 => continue,
+                        Some(&next_id) => {
+                            nodes[next_id].distance = distance + 1;
+                            nodes[next_id].parent = Some((id, command));
+                            next_id
+                        }
+                        None => {
+                            seen.insert(next.clone(), nodes.len());
+                            nodes.push(Node {
+                                state: next,
+                                distance: distance + 1,
+                                parent: Some((id, command)),
+                            });
+                            nodes.len() - 1
+                        }
+                    };
+                    queue.push(Reverse((cost, distance + 1, next_id)));
+                }
+            }
+        }
+    }
+
+    Err(PlanError::Unreachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stacks(s: &[&str]) -> Vec<Vec<Crate>> {
+        s.iter()
+            .map(|s| s.chars().map(Crate::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_reverse_in_place() {
+        let reverse = Command {
+            amount: 2,
+            from: 1,
+            to: 1,
+        };
+        for (initial, target) in [(&["AB"][..], &["BA"][..]), (&["AB", ""], &["BA", ""])] {
+            let (initial, target) = (stacks(initial), stacks(target));
+            let commands = plan(&initial, &target, CraneModel::CrateMover9000, None).unwrap();
+            assert_eq!(commands, [reverse]);
+            assert_eq!(
+                plan(&initial, &target, CraneModel::CrateMover9001, None).map(|c| c.len()),
+                if initial.len() == 1 {
+                    Err(PlanError::Unreachable)
+                } else {
+                    Ok(3)
+                }
+            );
+        }
+    }
+}
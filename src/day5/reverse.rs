@@ -0,0 +1,247 @@
+//! Works backwards from the answer: given the commands and the crates that should end up on top of
+//! the stacks, finds stacks to start from.
+
+use std::fmt;
+
+use super::{history::Operation, move_crates, Command, Crane, CraneModel, Crate};
+
+/// Why no starting stacks could be found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReverseError {
+    /// The command at `index` refers to a stack that doesn't exist.
+    BadStackIndex { index: usize, stack: usize },
+    /// The commands leave crates on more stacks than there are tops.
+    TooFewTops { required: usize, given: usize },
+    /// There are more tops than stacks to put them on.
+    TooManyTops { stacks: usize, given: usize },
+}
+
+impl fmt::Display for ReverseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReverseError::BadStackIndex { index, stack } => {
+                write!(f, "command {index} refers to stack {stack}, which does not exist")
+            }
+            ReverseError::TooFewTops { required, given } => write!(
+                f,
+                "the commands always leave crates on {required} stacks, but only {given} tops were given"
+            ),
+            ReverseError::TooManyTops { stacks, given } => {
+                write!(f, "{given} tops were given, but there are only {stacks} stacks")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReverseError {}
+
+/// Finds a crane with `stacks` stacks that ends up with `tops` as its stack tops after running
+/// `commands` with the given model.  Each character of `tops` is the label of one crate; every
+/// crate that doesn't end up on top is labelled `filler`.
+///
+/// The commands fix how much each stack's height changes, so the smallest starting heights that
+/// never run a stack dry are found first, with extra crates added to stacks that would otherwise
+/// end up empty until there is one non-empty stack per top.  The labelled final stacks are then
+/// run backwards through the inverse of each command to find where every crate started.
+///
+/// ```rust
+/// # use aoc_2022::day5::{*, reverse::*};
+/// let commands = [
+///     Command { amount: 1, from: 2, to: 1 },
+///     Command { amount: 3, from: 1, to: 3 },
+///     Command { amount: 2, from: 2, to: 1 },
+///     Command { amount: 1, from: 1, to: 2 },
+/// ];
+/// let model = CraneModel::CrateMover9000;
+/// let mut crane = reconstruct(&commands, 3, "XYZ", model, Crate::from('-')).unwrap();
+/// crane.process(model).unwrap();
+/// assert_eq!(crane.read_stack_tops_string(), "XYZ");
+///
+/// assert_eq!(
+///     reconstruct(&commands, 3, "XY", model, Crate::from('-')).unwrap_err(),
+///     ReverseError::TooFewTops { required: 3, given: 2 }
+/// );
+/// ```
+pub fn reconstruct(
+    commands: &[Command],
+    stacks: usize,
+    tops: &str,
+    model: CraneModel,
+    filler: Crate,
+) -> Result<Crane, ReverseError> {
+    // net change in height of each stack so far, and the smallest starting height that keeps
+    // every stack from running out of crates.
+    let mut change = vec![0i64; stacks];
+    let mut initial = vec![0i64; stacks];
+    for (index, command) in commands.iter().enumerate() {
+        for stack in [command.from, command.to] {
+            if stack == 0 || stack > stacks {
+                return Err(ReverseError::BadStackIndex { index, stack });
+            }
+        }
+
+        let amount = command.amount as i64;
+        change[command.from - 1] -= amount;
+        initial[command.from - 1] = initial[command.from - 1].max(-change[command.from - 1]);
+        change[command.to - 1] += amount;
+    }
+
+    let mut heights: Vec<usize> = initial
+        .iter()
+        .zip(&change)
+        .map(|(initial, change)| (initial + change) as usize)
+        .collect();
+    let tops: Vec<char> = tops.chars().collect();
+    if tops.len() > stacks {
+        return Err(ReverseError::TooManyTops {
+            stacks,
+            given: tops.len(),
+        });
+    }
+    let required = heights.iter().filter(|&&h| h > 0).count();
+    if required > tops.len() {
+        return Err(ReverseError::TooFewTops {
+            required,
+            given: tops.len(),
+        });
+    }
+
+    // an extra crate at the bottom of a stack stays there throughout, so it's enough to give
+    // stacks that would end up empty a single crate.
+    let mut extra = tops.len() - required;
+    for height in heights.iter_mut().filter(|h| **h == 0) {
+        if extra == 0 {
+            break;
+        }
+        *height = 1;
+        extra -= 1;
+    }
+
+    let mut tops = tops.into_iter();
+    let mut state: Vec<Vec<Crate>> = heights
+        .iter()
+        .map(|&height| {
+            let mut stack = vec![filler.clone(); height];
+            if let Some(top) = stack.last_mut() {
+                *top = Crate::from(tops.next().expect("one top per non-empty stack"));
+            }
+            stack
+        })
+        .collect();
+
+    for &command in commands.iter().rev() {
+        let inverse = Operation { command, model }.inverse();
+        move_crates(&mut state, &inverse.command, inverse.model);
+    }
+
+    Ok(Crane::new(state, commands.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconstruct_puzzle_input() {
+        let input = include_str!("../../input/2022/day5.txt");
+        let crane = Crane::parse(input).unwrap();
+        for model in [CraneModel::CrateMover9000, CraneModel::CrateMover9001] {
+            let stacks = crane.stacks.len();
+            let mut found = reconstruct(
+                &crane.commands,
+                stacks,
+                "ADVENTOFC",
+                model,
+                Crate::from('?'),
+            )
+            .unwrap();
+            found.process(model).unwrap();
+            assert_eq!(found.read_stack_tops_string(), "ADVENTOFC");
+        }
+    }
+
+    fn commands() -> Vec<Command> {
+        vec![
+            Command {
+                amount: 2,
+                from: 1,
+                to: 2,
+            },
+            Command {
+                amount: 1,
+                from: 2,
+                to: 3,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_bad_stack_index() {
+        let model = CraneModel::CrateMover9000;
+        let mut from_zero = commands();
+        from_zero[1].from = 0;
+        let mut to_missing = commands();
+        to_missing[1].to = 4;
+        for (commands, stack) in [(from_zero, 0), (to_missing, 4)] {
+            assert_eq!(
+                reconstruct(&commands, 3, "XYZ", model, Crate::from('-')).unwrap_err(),
+                ReverseError::BadStackIndex { index: 1, stack }
+            );
+        }
+    }
+
+    #[test]
+    fn test_too_few_tops() {
+        // stacks 2 and 3 always end up with crates on them
+        let error = reconstruct(
+            &commands(),
+            3,
+            "X",
+            CraneModel::CrateMover9001,
+            Crate::from('-'),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            ReverseError::TooFewTops {
+                required: 2,
+                given: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_too_many_tops() {
+        let error = reconstruct(
+            &commands(),
+            3,
+            "WXYZ",
+            CraneModel::CrateMover9000,
+            Crate::from('-'),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            ReverseError::TooManyTops {
+                stacks: 3,
+                given: 4
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "4 tops were given, but there are only 3 stacks"
+        );
+
+        // as many tops as stacks is fine: the empty stack gets a crate of its own
+        let mut crane = reconstruct(
+            &commands(),
+            3,
+            "XYZ",
+            CraneModel::CrateMover9000,
+            Crate::from('-'),
+        )
+        .unwrap();
+        crane.process(CraneModel::CrateMover9000).unwrap();
+        assert_eq!(crane.read_stack_tops_string(), "XYZ");
+    }
+}
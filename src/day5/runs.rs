@@ -0,0 +1,322 @@
+//! A representation of the stacks that moves whole runs of crates at a time, for inputs where
+//! commands move huge numbers of crates.
+//!
+//! Crates never change once the input has been parsed, only their order does, so every crate is
+//! stored exactly once and each stack is a sequence of [`Run`]s pointing at contiguous pieces of
+//! that storage.  The sequence is kept in a treap ordered by position in the stack, where every
+//! node knows how many crates its subtree holds and can be marked as reversed without visiting it.
+//!
+//! Moving the top `N` crates splits the source stack's treap at its `N`th crate from the top,
+//! splitting at most one run, marks the split-off piece as reversed for the CrateMover 9000, and
+//! joins it onto the target's treap.  Each of those steps takes expected O(log n) time for `n`
+//! runs, so every command does, no matter how many crates it moves or how fragmented the stacks
+//! have become.  Every command adds at most one run, so `n` never exceeds the number of stacks
+//! plus the number of commands.
+
+use super::{check_command, Command, CommandErrorKind, CraneModel, Crate};
+
+/// A contiguous piece of crate storage, from bottom to top unless `reversed` is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Run {
+    start: usize,
+    len: usize,
+    reversed: bool,
+}
+
+impl Run {
+    /// Splits off the top `amount` crates, which must be fewer than the run holds.  Returns the
+    /// bottom and top parts.
+    fn split(self, amount: usize) -> (Run, Run) {
+        let rest = self.len - amount;
+        if self.reversed {
+            (
+                Run {
+                    start: self.start + amount,
+                    len: rest,
+                    reversed: true,
+                },
+                Run {
+                    start: self.start,
+                    len: amount,
+                    reversed: true,
+                },
+            )
+        } else {
+            (
+                Run {
+                    start: self.start,
+                    len: rest,
+                    reversed: false,
+                },
+                Run {
+                    start: self.start + rest,
+                    len: amount,
+                    reversed: false,
+                },
+            )
+        }
+    }
+
+    /// The same crates in the opposite order.
+    fn flip(self) -> Run {
+        Run {
+            reversed: !self.reversed,
+            ..self
+        }
+    }
+
+    /// Index in the crate storage of the top crate.
+    fn top(&self) -> usize {
+        if self.reversed {
+            self.start
+        } else {
+            self.start + self.len - 1
+        }
+    }
+}
+
+/// Marks a missing child.
+const NIL: usize = usize::MAX;
+
+/// A node of a stack's treap, holding one run.
+#[derive(Debug, Clone)]
+struct Node {
+    run: Run,
+    /// The runs below this one in the stack.
+    left: usize,
+    /// The runs above this one in the stack.
+    right: usize,
+    priority: u64,
+    /// Number of crates in this node's subtree.
+    size: usize,
+    /// Set when the whole subtree, including this node's run, still has to be reversed.
+    reversed: bool,
+}
+
+/// Stacks of crates stored as runs.
+#[derive(Debug, Clone)]
+pub struct RunStacks {
+    crates: Vec<Crate>,
+    nodes: Vec<Node>,
+    /// The root node of each stack's treap.
+    roots: Vec<usize>,
+    heights: Vec<usize>,
+    /// State of the generator handing out node priorities.
+    seed: u64,
+}
+
+impl RunStacks {
+    /// Converts stacks given bottom to top into runs.
+    pub fn new(stacks: Vec<Vec<Crate>>) -> Self {
+        let mut runs = Self {
+            crates: vec![],
+            nodes: vec![],
+            roots: vec![],
+            heights: stacks.iter().map(Vec::len).collect(),
+            seed: 0x2022,
+        };
+        for stack in stacks {
+            let root = match stack.len() {
+                0 => NIL,
+                len => runs.node(Run {
+                    start: runs.crates.len(),
+                    len,
+                    reversed: false,
+                }),
+            };
+            runs.roots.push(root);
+            runs.crates.extend(stack);
+        }
+
+        runs
+    }
+
+    /// Number of crates on each stack.
+    pub fn heights(&self) -> &[usize] {
+        &self.heights
+    }
+
+    /// The crate on top of the 0-indexed `stack`, if there is one.
+    pub fn top(&self, stack: usize) -> Option<&Crate> {
+        // follow the topmost path down, keeping track of which way up each subtree is
+        let mut node = self.roots[stack];
+        let mut reversed = false;
+        while node != NIL {
+            let n = &self.nodes[node];
+            reversed ^= n.reversed;
+            let above = if reversed { n.left } else { n.right };
+            if above == NIL {
+                let run = if reversed { n.run.flip() } else { n.run };
+                return Some(&self.crates[run.top()]);
+            }
+            node = above;
+        }
+
+        None
+    }
+
+    /// Carries out a single command in expected O(log n) time for `n` runs.  The stacks are left
+    /// untouched if it can't be carried out.
+    pub fn apply(&mut self, command: &Command, model: CraneModel) -> Result<(), CommandErrorKind> {
+        check_command(&self.heights, |&h| h, command)?;
+        let (from, to) = (command.from - 1, command.to - 1);
+
+        let (rest, moved) = self.split(self.roots[from], self.heights[from] - command.amount);
+        // one crate at a time: the former top crate ends up at the bottom.  All at once: the
+        // crates keep their order.
+        if model == CraneModel::CrateMover9000 && moved != NIL {
+            self.nodes[moved].reversed ^= true;
+        }
+        self.roots[from] = rest;
+        self.roots[to] = self.merge(self.roots[to], moved);
+
+        self.heights[from] -= command.amount;
+        self.heights[to] += command.amount;
+        Ok(())
+    }
+
+    /// Adds a node holding `run` on its own.
+    fn node(&mut self, run: Run) -> usize {
+        // xorshift, so priorities are spread out but every run is the same
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.nodes.push(Node {
+            run,
+            left: NIL,
+            right: NIL,
+            priority: self.seed,
+            size: run.len,
+            reversed: false,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn size(&self, node: usize) -> usize {
+        if node == NIL {
+            0
+        } else {
+            self.nodes[node].size
+        }
+    }
+
+    fn update(&mut self, node: usize) {
+        let n = &self.nodes[node];
+        let size = n.run.len + self.size(n.left) + self.size(n.right);
+        self.nodes[node].size = size;
+    }
+
+    /// Carries out a pending reversal of `node`'s subtree one level down.
+    fn push_down(&mut self, node: usize) {
+        let n = &mut self.nodes[node];
+        if !n.reversed {
+            return;
+        }
+        n.reversed = false;
+        n.run = n.run.flip();
+        std::mem::swap(&mut n.left, &mut n.right);
+        let (left, right) = (n.left, n.right);
+        for child in [left, right] {
+            if child != NIL {
+                self.nodes[child].reversed ^= true;
+            }
+        }
+    }
+
+    /// Joins two treaps, with every crate in `below` under every crate in `above`.
+    fn merge(&mut self, below: usize, above: usize) -> usize {
+        if below == NIL {
+            return above;
+        }
+        if above == NIL {
+            return below;
+        }
+
+        if self.nodes[below].priority > self.nodes[above].priority {
+            self.push_down(below);
+            let right = self.merge(self.nodes[below].right, above);
+            self.nodes[below].right = right;
+            self.update(below);
+            below
+        } else {
+            self.push_down(above);
+            let left = self.merge(below, self.nodes[above].left);
+            self.nodes[above].left = left;
+            self.update(above);
+            above
+        }
+    }
+
+    /// Splits a treap into its bottom `count` crates and the rest, splitting a run if the cut
+    /// falls inside one.
+    fn split(&mut self, node: usize, count: usize) -> (usize, usize) {
+        if node == NIL {
+            return (NIL, NIL);
+        }
+        self.push_down(node);
+
+        let Node {
+            run, left, right, ..
+        } = self.nodes[node];
+        let below = self.size(left);
+        if count <= below {
+            let (bottom, top) = self.split(left, count);
+            self.nodes[node].left = top;
+            self.update(node);
+            (bottom, node)
+        } else if count >= below + run.len {
+            let (bottom, top) = self.split(right, count - below - run.len);
+            self.nodes[node].right = bottom;
+            self.update(node);
+            (node, top)
+        } else {
+            // the cut is inside this node's run: keep its bottom part here, and put its top part
+            // in a new node under the runs above it
+            let (kept, moved) = run.split(below + run.len - count);
+            self.nodes[node].run = kept;
+            self.nodes[node].right = NIL;
+            self.update(node);
+            let moved = self.node(moved);
+            let top = self.merge(moved, right);
+            (node, top)
+        }
+    }
+
+    /// Appends the runs of `node`'s subtree to `runs`, bottom to top.
+    fn collect_runs(&mut self, node: usize, runs: &mut Vec<Run>) {
+        if node == NIL {
+            return;
+        }
+        self.push_down(node);
+        let Node {
+            run, left, right, ..
+        } = self.nodes[node];
+        self.collect_runs(left, runs);
+        runs.push(run);
+        self.collect_runs(right, runs);
+    }
+
+    /// Converts back into stacks listed bottom to top.
+    pub fn into_stacks(mut self) -> Vec<Vec<Crate>> {
+        let mut crates: Vec<Option<Crate>> = std::mem::take(&mut self.crates)
+            .into_iter()
+            .map(Some)
+            .collect();
+        (0..self.roots.len())
+            .map(|stack| {
+                let mut runs = vec![];
+                self.collect_runs(self.roots[stack], &mut runs);
+                let mut stack = vec![];
+                for run in runs {
+                    let range = run.start..run.start + run.len;
+                    if run.reversed {
+                        stack.extend(range.rev().filter_map(|i| crates[i].take()));
+                    } else {
+                        stack.extend(range.filter_map(|i| crates[i].take()));
+                    }
+                }
+                stack
+            })
+            .collect()
+    }
+}
@@ -1,8 +1,378 @@
-use std::collections::HashMap;
+pub mod framing;
 
-use aoc_runner_derive::aoc;
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    ops::Range,
+};
 
+use aoc_runner_derive::{aoc, aoc_generator};
+
+use crate::normalize::normalize_for_day;
+
+/// Remembers where each symbol was last seen.  Bytes and the first 256 code points live in a
+/// fixed table; anything else falls back to a map.
+#[derive(Debug, Clone)]
+struct LastSeen {
+    /// One more than the position each symbol was last seen at, or 0 if it hasn't been.
+    table: [usize; 256],
+    other: HashMap<u32, usize>,
+}
+
+impl Default for LastSeen {
+    fn default() -> Self {
+        Self {
+            table: [0; 256],
+            other: HashMap::new(),
+        }
+    }
+}
+
+impl LastSeen {
+    /// Records that `symbol` was seen at `position`, returning where it was seen before.
+    fn replace(&mut self, symbol: u32, position: usize) -> Option<usize> {
+        match self.table.get_mut(symbol as usize) {
+            Some(slot) => {
+                let previous = std::mem::replace(slot, position + 1);
+                previous.checked_sub(1)
+            }
+            None => self.other.insert(symbol, position),
+        }
+    }
+}
+
+/// One bit per possible byte.
+#[derive(Debug, Clone, Default)]
+struct ByteSet([u64; 4]);
+
+impl ByteSet {
+    /// Adds `byte`, returning `false` if it was already there.
+    fn insert(&mut self, byte: u8) -> bool {
+        let (word, bit) = (usize::from(byte / 64), 1 << (byte % 64));
+        let added = self.0[word] & bit == 0;
+        self.0[word] |= bit;
+        added
+    }
+}
+
+/// A sliding window over a stream of symbols that tracks the longest run of distinct symbols
+/// ending at the most recent one.  When a symbol repeats, the window skips straight past its
+/// previous occurrence, so each symbol costs a single table lookup no matter how long the window
+/// is.  Streams of bytes can also be searched in bulk with [`Window::push_until`], which skips
+/// over most of them without a lookup at all.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Window {
+    last_seen: LastSeen,
+    /// Position of the first symbol in the current run.
+    start: usize,
+    /// Number of symbols pushed so far.
+    position: usize,
+}
+
+impl Window {
+    /// Adds the next symbol, returning the length of the distinct run that now ends with it.
+    pub(crate) fn push(&mut self, symbol: u32) -> usize {
+        if let Some(previous) = self.last_seen.replace(symbol, self.position) {
+            self.start = self.start.max(previous + 1);
+        }
+        self.position += 1;
+        self.run()
+    }
+
+    /// Length of the distinct run ending at the most recent symbol.
+    fn run(&self) -> usize {
+        self.position - self.start
+    }
+
+    /// Pushes bytes until one of them completes a run of at least `n` distinct symbols, returning
+    /// how many were pushed, or pushes them all and returns `None`.  The window ends up just as if
+    /// each byte had gone through [`Window::push`].
+    ///
+    /// Only the first `n` bytes, where a run could carry on from before them, are pushed one at a
+    /// time.  After that, each window of `n` bytes is checked from its end backwards with a
+    /// bitmask.  When a byte turns out to be repeated, every window that still contains both
+    /// copies must fail too, so the search skips straight to the first window that starts after
+    /// the later copy.
+    pub(crate) fn push_until(&mut self, bytes: &[u8], n: usize) -> Option<usize> {
+        let head = n.max(1).min(bytes.len());
+        for (i, &byte) in bytes[..head].iter().enumerate() {
+            if self.push(u32::from(byte)) >= n {
+                return Some(i + 1);
+            }
+        }
+
+        let base = self.position - head;
+        let mut end = head + 1;
+        while end <= bytes.len() {
+            let mut seen = ByteSet::default();
+            match (end - n..end).rev().find(|&i| !seen.insert(bytes[i])) {
+                Some(repeat) => end = repeat + 1 + n,
+                None => {
+                    self.catch_up(bytes, base, head, end);
+                    return Some(end);
+                }
+            }
+        }
+        self.catch_up(bytes, base, head, bytes.len());
+
+        None
+    }
+
+    /// Brings the window from `pushed` up to `end` in `bytes`, which start at stream position
+    /// `base`, by pushing only the distinct run that ends there.  The skipped bytes never reach the
+    /// last-seen table, but they all come before the run starts, so their stale entries can't move
+    /// it.
+    fn catch_up(&mut self, bytes: &[u8], base: usize, pushed: usize, end: usize) {
+        let mut seen = ByteSet::default();
+        let mut from = end;
+        while from > pushed && seen.insert(bytes[from - 1]) {
+            from -= 1;
+        }
+        if from > pushed {
+            // the byte before `from` repeats one in the run, so the run starts at `from`
+            self.position = base + from;
+            self.start = self.position;
+        }
+        for &byte in &bytes[from..end] {
+            self.push(u32::from(byte));
+        }
+    }
+}
+
+/// An iterator over every position in a stream where the preceding `n` characters are all
+/// distinct.  Positions count characters from the start of the stream, so they're the number of
+/// characters that need to be read to reach each marker.
+pub struct Markers<'a> {
+    chars: std::str::Chars<'a>,
+    /// Set if the stream is ASCII, so characters are bytes and can be searched in bulk.
+    ascii: bool,
+    window: Window,
+    n: usize,
+    /// Set once position 0 has been checked.
+    started: bool,
+}
+
+impl Iterator for Markers<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if !self.started {
+            self.started = true;
+            if self.n == 0 {
+                return Some(0);
+            }
+        }
+
+        if self.ascii {
+            let rest = self.chars.as_str();
+            let pushed = self.window.push_until(rest.as_bytes(), self.n);
+            self.chars = rest[pushed.unwrap_or(rest.len())..].chars();
+            return pushed.map(|_| self.window.position);
+        }
+
+        for c in self.chars.by_ref() {
+            if self.window.push(u32::from(c)) >= self.n {
+                return Some(self.window.position);
+            }
+        }
+
+        None
+    }
+}
+
+/// Finds every position where the preceding `n` characters are all distinct.
+///
+/// ```rust
+/// # use aoc_2022::day6::*;
+/// let markers: Vec<_> = markers("abcabbcd", 3).collect();
+/// assert_eq!(markers, vec![3, 4, 5, 8]);
+/// ```
+pub fn markers(input: &str, n: usize) -> Markers<'_> {
+    Markers {
+        chars: input.chars(),
+        ascii: input.is_ascii(),
+        window: Window::default(),
+        n,
+        started: false,
+    }
+}
+
+/// Finds how many characters need to be read before the last `n` of them are all distinct.
+///
+/// ```rust
+/// # use aoc_2022::day6::*;
+/// assert_eq!(find_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 4), Some(7));
+/// assert_eq!(find_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14), Some(19));
+/// assert_eq!(find_marker("aaaa", 2), None);
+/// ```
+pub fn find_marker(input: &str, n: usize) -> Option<usize> {
+    markers(input, n).next()
+}
+
+/// Finds the longest stretch of the input in which every character is distinct, as a range of
+/// character positions.  The earliest one wins if there's a tie.
+///
+/// ```rust
+/// # use aoc_2022::day6::*;
+/// assert_eq!(longest_distinct("abcabcdbb"), 3..7);
+/// assert_eq!(longest_distinct(""), 0..0);
+/// ```
+pub fn longest_distinct(input: &str) -> Range<usize> {
+    let mut window = Window::default();
+    let mut longest = 0..0;
+    if input.is_ascii() {
+        // only look for runs longer than the longest so far
+        let mut rest = input.as_bytes();
+        while let Some(pushed) = window.push_until(rest, longest.len() + 1) {
+            rest = &rest[pushed..];
+            longest = window.position - window.run()..window.position;
+        }
+        return longest;
+    }
+
+    for c in input.chars() {
+        let len = window.push(u32::from(c));
+        if len > longest.len() {
+            longest = window.position - len..window.position;
+        }
+    }
+
+    longest
+}
+
+/// The markers the communication device looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerKind {
+    /// Four distinct bytes in a row.
+    StartOfPacket,
+    /// Fourteen distinct bytes in a row.
+    StartOfMessage,
+}
+
+impl MarkerKind {
+    /// How many distinct bytes in a row make up the marker.
+    pub fn width(&self) -> usize {
+        match self {
+            MarkerKind::StartOfPacket => 4,
+            MarkerKind::StartOfMessage => 14,
+        }
+    }
+}
+
+/// A marker found by a [`MarkerDetector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkerEvent {
+    pub kind: MarkerKind,
+    /// Number of bytes read from the start of the stream when the marker was completed.
+    pub offset: usize,
+}
+
+/// Looks for the start-of-packet and start-of-message markers in a stream that arrives a chunk
+/// at a time, such as from a socket or pipe.  The window is kept between chunks, so a marker can
+/// be split across any number of them.
+///
+/// ```rust
+/// # use aoc_2022::day6::*;
+/// let mut detector = MarkerDetector::new();
+/// let mut events = vec![];
+/// for chunk in ["mjq", "jpqmgb", "", "ljsphdztnv", "jfqwrcgsmlb"] {
+///     events.extend(detector.push(chunk.as_bytes()));
+/// }
+/// assert_eq!(
+///     events,
+///     vec![
+///         MarkerEvent { kind: MarkerKind::StartOfPacket, offset: 7 },
+///         MarkerEvent { kind: MarkerKind::StartOfMessage, offset: 19 },
+///     ]
+/// );
+/// assert!(detector.is_done());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MarkerDetector {
+    window: Window,
+    found_packet: bool,
+    found_message: bool,
+}
+
+impl MarkerDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of bytes seen so far.
+    pub fn offset(&self) -> usize {
+        self.window.position
+    }
+
+    /// Have both markers been found?
+    pub fn is_done(&self) -> bool {
+        self.found_packet && self.found_message
+    }
+
+    /// Feeds the next chunk of the stream, returning any markers completed by it.  Once both
+    /// markers have been found, the rest of the stream is only counted.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<MarkerEvent> {
+        let mut events = vec![];
+        let mut rest = chunk;
+        while !self.is_done() {
+            let next = match self.found_packet {
+                false => MarkerKind::StartOfPacket,
+                true => MarkerKind::StartOfMessage,
+            };
+            let Some(pushed) = self.window.push_until(rest, next.width()) else {
+                return events;
+            };
+            rest = &rest[pushed..];
+
+            let run = self.window.run();
+            for (kind, found) in [
+                (MarkerKind::StartOfPacket, &mut self.found_packet),
+                (MarkerKind::StartOfMessage, &mut self.found_message),
+            ] {
+                if !*found && run >= kind.width() {
+                    *found = true;
+                    events.push(MarkerEvent {
+                        kind,
+                        offset: self.window.position,
+                    });
+                }
+            }
+        }
+
+        self.window.position += rest.len();
+        events
+    }
+
+    /// Reads from `reader` until both markers have been found or the stream ends, returning the
+    /// markers found along the way.
+    pub fn read_from(&mut self, mut reader: impl Read) -> io::Result<Vec<MarkerEvent>> {
+        let mut events = vec![];
+        let mut buf = [0; 4096];
+        while !self.is_done() {
+            let read = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            events.extend(self.push(&buf[..read]));
+        }
+
+        Ok(events)
+    }
+}
+
+/// Finds how many characters need to be read before the last `N` of them are all distinct.
 pub fn find_chars<const N: usize>(input: &str) -> anyhow::Result<u32> {
+    match find_marker(input, N) {
+        Some(end) => Ok(end as u32),
+        None => anyhow::bail!("Failed to find unique sequential chars of length {N}"),
+    }
+}
+
+/// The original implementation of [`find_chars`], which keeps a count of every character in the
+/// window.  Kept around to benchmark against.
+pub fn find_chars_hashmap<const N: usize>(input: &str) -> anyhow::Result<u32> {
     let mut counter = HashMap::new();
     for c in input.chars().take(N) {
         *counter.entry(c).or_default() += 1
@@ -11,11 +381,7 @@ pub fn find_chars<const N: usize>(input: &str) -> anyhow::Result<u32> {
         return Ok(N as u32);
     }
 
-    for ((first, last), count) in input
-        .chars()
-        .zip(input.chars().skip(N))
-        .zip(((N + 1)..).into_iter())
-    {
+    for ((first, last), count) in input.chars().zip(input.chars().skip(N)).zip((N + 1)..) {
         let x: &mut u32 = counter.get_mut(&first).unwrap();
         *x -= 1;
         if *x == 0 {
@@ -31,6 +397,14 @@ pub fn find_chars<const N: usize>(input: &str) -> anyhow::Result<u32> {
     anyhow::bail!("Failed to find unique sequential chars of length {N}");
 }
 
+/// Normalizes the input before the cargo-aoc runner hands it to any of the solvers below.
+#[aoc_generator(day6)]
+#[aoc_generator(day6, part1, HashMap)]
+#[aoc_generator(day6, part2, HashMap)]
+fn generator(input: &str) -> String {
+    normalize_for_day(6, input)
+}
+
 /// Finds the first four characters that are sequentially distinct.  Returns the index of the last
 /// distinct character.
 ///
@@ -62,3 +436,121 @@ pub fn part1(input: &str) -> anyhow::Result<u32> {
 pub fn part2(input: &str) -> anyhow::Result<u32> {
     find_chars::<14>(input)
 }
+
+#[aoc(day6, part1, HashMap)]
+pub fn part1_hashmap(input: &str) -> anyhow::Result<u32> {
+    find_chars_hashmap::<4>(input)
+}
+
+#[aoc(day6, part2, HashMap)]
+pub fn part2_hashmap(input: &str) -> anyhow::Result<u32> {
+    find_chars_hashmap::<14>(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_detector_chunk_boundaries() {
+        let input = include_str!("../input/2022/day6.txt").trim_end();
+        let expected = vec![
+            MarkerEvent {
+                kind: MarkerKind::StartOfPacket,
+                offset: part1(input).unwrap() as usize,
+            },
+            MarkerEvent {
+                kind: MarkerKind::StartOfMessage,
+                offset: part2(input).unwrap() as usize,
+            },
+        ];
+
+        for chunk_size in [1, 2, 3, 7, 13, 64, 4096] {
+            let mut detector = MarkerDetector::new();
+            let mut events = vec![];
+            for chunk in input.as_bytes().chunks(chunk_size) {
+                events.extend(detector.push(chunk));
+            }
+            assert_eq!(events, expected, "chunk size {chunk_size}");
+            assert_eq!(detector.offset(), input.len());
+        }
+
+        let mut detector = MarkerDetector::new();
+        assert_eq!(detector.read_from(input.as_bytes()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_markers_match_brute_force() {
+        let input = include_str!("../input/2022/day6.txt").trim_end();
+        let chars: Vec<char> = input.chars().collect();
+        for n in [1, 4, 14, 20] {
+            let expected: Vec<usize> = (n..=chars.len())
+                .filter(|&end| {
+                    let window = &chars[end - n..end];
+                    window
+                        .iter()
+                        .enumerate()
+                        .all(|(i, c)| !window[i + 1..].contains(c))
+                })
+                .collect();
+            assert_eq!(markers(input, n).collect::<Vec<_>>(), expected, "n = {n}");
+            assert_eq!(find_marker(input, n), expected.first().copied(), "n = {n}");
+        }
+
+        let longest = longest_distinct(input);
+        assert_eq!(markers(input, longest.len()).next(), Some(longest.end));
+        assert_eq!(markers(input, longest.len() + 1).next(), None);
+        assert_eq!(find_marker(input, longest.len() + 1), None);
+        assert_eq!(find_marker(input, 0), Some(0));
+    }
+
+    #[test]
+    fn test_matches_hashmap() {
+        let input = include_str!("../input/2022/day6.txt").trim_end();
+        let inputs = [
+            input,
+            "mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+            "aaaaaaaaaaaaaaaaaaaa",
+            "ab",
+            "ééaéaébcdéfghijklmnopq",
+            "αβγαδεζηθικλμνξοπ",
+        ];
+        for input in inputs {
+            assert_eq!(
+                find_chars::<4>(input).ok(),
+                find_chars_hashmap::<4>(input).ok(),
+                "{input}"
+            );
+            assert_eq!(
+                find_chars::<14>(input).ok(),
+                find_chars_hashmap::<14>(input).ok(),
+                "{input}"
+            );
+        }
+    }
+    proptest! {
+        #[test]
+        fn bytes_match_chars(input in "[a-f]{0,80}", n in 0..8usize, chunk_size in 1..10usize) {
+            // the same stream with every letter swapped for a Greek one can't be searched as bytes
+            let greek: String = input
+                .chars()
+                .map(|c| char::from_u32(u32::from(c) - u32::from('a') + u32::from('α')).unwrap())
+                .collect();
+            let expected: Vec<usize> = markers(&greek, n).collect();
+            prop_assert_eq!(markers(&input, n).collect::<Vec<_>>(), expected);
+            prop_assert_eq!(longest_distinct(&input), longest_distinct(&greek));
+
+            let mut detector = MarkerDetector::new();
+            let events: Vec<MarkerEvent> = input
+                .as_bytes()
+                .chunks(chunk_size)
+                .flat_map(|chunk| detector.push(chunk))
+                .collect();
+            let packet = markers(&greek, 4).next();
+            let message = markers(&greek, 14).next();
+            prop_assert_eq!(events.first().map(|e| e.offset), packet);
+            prop_assert_eq!(events.get(1).map(|e| e.offset), message);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Splits the datastream into messages once the start-of-message marker has been found.
+//!
+//! After the first start-of-message marker, every message is terminated by another one: the
+//! stream is scanned afresh from the end of each marker, and the bytes before the next run of
+//! [`MarkerKind::StartOfMessage`] distinct bytes make up the message.
+//!
+//! A message can't contain a run of that many distinct bytes itself, so [`encode`] stuffs an extra
+//! copy of a byte whenever a run is one short of being a marker, and [`Message::payload`] drops
+//! those copies again.  A marker that [`encode`] writes after a message starts with a repeat of
+//! the message's last byte, so the end of a message can never combine with the marker to complete
+//! it early.
+
+use std::borrow::Cow;
+
+use super::{MarkerKind, Window};
+
+const MARKER_WIDTH: usize = 14;
+
+/// Bytes used to build markers; one more than a marker needs, so there are always enough to
+/// avoid a given byte.
+const MARKER_BYTES: &[u8; MARKER_WIDTH + 1] = b"abcdefghijklmno";
+
+/// A message found in a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Message<'a> {
+    /// Offset of the first byte of the message from the start of the stream.
+    pub offset: usize,
+    /// The bytes of the message as they appear in the stream, including any stuffed bytes.
+    pub raw: &'a [u8],
+    /// Was the message followed by a marker?  Only the last message in a stream can be
+    /// unterminated.
+    pub terminated: bool,
+}
+
+impl<'a> Message<'a> {
+    /// The message with any bytes stuffed in by [`encode`] taken back out.
+    pub fn payload(&self) -> Cow<'a, [u8]> {
+        let mut window = Window::default();
+        let mut run = 0;
+        let mut previous = None;
+        let mut stuffed = vec![];
+        for (i, &byte) in self.raw.iter().enumerate() {
+            if run == MARKER_WIDTH - 1 && previous == Some(byte) {
+                stuffed.push(i);
+            }
+            run = window.push(u32::from(byte));
+            previous = Some(byte);
+        }
+
+        if stuffed.is_empty() {
+            return Cow::Borrowed(self.raw);
+        }
+        let mut stuffed = stuffed.into_iter().peekable();
+        Cow::Owned(
+            self.raw
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| stuffed.next_if_eq(i).is_none())
+                .map(|(_, &byte)| byte)
+                .collect(),
+        )
+    }
+}
+
+/// An iterator over the messages in a stream.
+pub struct Messages<'a> {
+    stream: &'a [u8],
+    /// Where the next message starts, or `None` before the start-of-message marker is found.
+    position: Option<usize>,
+}
+
+impl Messages<'_> {
+    /// Finds the end of the first marker at or after `from`, scanning with a fresh window.
+    fn next_marker(&self, from: usize) -> Option<usize> {
+        let mut window = Window::default();
+        self.stream[from..]
+            .iter()
+            .position(|&byte| window.push(u32::from(byte)) >= MARKER_WIDTH)
+            .map(|i| from + i + 1)
+    }
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = Message<'a>;
+
+    fn next(&mut self) -> Option<Message<'a>> {
+        let start = match self.position {
+            Some(start) => start,
+            None => {
+                let start = self.next_marker(0)?;
+                self.position = Some(start);
+                start
+            }
+        };
+        if start >= self.stream.len() {
+            return None;
+        }
+
+        let (end, next, terminated) = match self.next_marker(start) {
+            Some(end) => (end - MARKER_WIDTH, end, true),
+            None => (self.stream.len(), self.stream.len(), false),
+        };
+        self.position = Some(next);
+
+        Some(Message {
+            offset: start,
+            raw: &self.stream[start..end],
+            terminated,
+        })
+    }
+}
+
+/// Splits `stream` into the messages following its start-of-message marker.
+///
+/// ```rust
+/// # use aoc_2022::day6::framing::*;
+/// let payloads: [&[u8]; 3] = [b"hello", b"", b"abcdefghijklmnopqrstuvwxyz"];
+/// let stream = encode(payloads);
+/// let decoded: Vec<_> = messages(&stream).map(|m| m.payload().into_owned()).collect();
+/// assert_eq!(decoded, payloads);
+///
+/// let first = messages(&stream).next().unwrap();
+/// assert_eq!((first.offset, first.raw), (14, &b"hello"[..]));
+/// ```
+pub fn messages(stream: &[u8]) -> Messages<'_> {
+    Messages {
+        stream,
+        position: None,
+    }
+}
+
+/// Builds a stream holding the given payloads: a start-of-message marker, then each payload
+/// followed by a marker of its own.
+pub fn encode<I>(payloads: I) -> Vec<u8>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    debug_assert_eq!(MarkerKind::StartOfMessage.width(), MARKER_WIDTH);
+
+    let mut stream = MARKER_BYTES[..MARKER_WIDTH].to_vec();
+    for payload in payloads {
+        let mut window = Window::default();
+        let mut last = None;
+        for &byte in payload.as_ref() {
+            stream.push(byte);
+            if window.push(u32::from(byte)) == MARKER_WIDTH - 1 {
+                // one more distinct byte would look like a marker, so repeat this one
+                stream.push(byte);
+                window.push(u32::from(byte));
+            }
+            last = Some(byte);
+        }
+
+        match last {
+            Some(last) => {
+                stream.push(last);
+                stream.extend(
+                    MARKER_BYTES
+                        .iter()
+                        .filter(|&&b| b != last)
+                        .take(MARKER_WIDTH - 1),
+                );
+            }
+            None => stream.extend(&MARKER_BYTES[..MARKER_WIDTH]),
+        }
+    }
+
+    stream
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day6::{MarkerDetector, MarkerEvent};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn encode_round_trips(
+            payloads in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..100), 0..10)
+        ) {
+            let stream = encode(&payloads);
+            let decoded: Vec<_> = messages(&stream).collect();
+            prop_assert!(decoded.iter().all(|m| m.terminated));
+            let decoded: Vec<_> = decoded.iter().map(|m| m.payload().into_owned()).collect();
+            prop_assert_eq!(decoded, payloads);
+
+            let mut detector = MarkerDetector::new();
+            prop_assert_eq!(
+                detector.push(&stream),
+                vec![
+                    MarkerEvent { kind: MarkerKind::StartOfPacket, offset: 4 },
+                    MarkerEvent { kind: MarkerKind::StartOfMessage, offset: 14 },
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_unterminated_message() {
+        let stream = b"aabcdefghijklmnopqrstuvwxyz";
+        let found: Vec<_> = messages(stream).collect();
+        assert_eq!(
+            found,
+            vec![Message {
+                offset: 15,
+                raw: b"opqrstuvwxyz",
+                terminated: false,
+            }]
+        );
+    }
+}
@@ -0,0 +1,410 @@
+//! Seeded generators for every day's puzzle input.  Each generator builds its input so that the
+//! answers are known by construction, and returns them alongside the input.
+
+use std::ops::RangeInclusive;
+
+use anyhow::{bail, Result};
+
+use crate::{
+    day5::{Command, Crane, Crate},
+    day6::Window,
+};
+
+/// A small, fast pseudo-random number generator (SplitMix64), so a seed always produces the same
+/// input no matter which platform or dependency versions are in use.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`, which must not be empty.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// A number in the given range, which must not be empty.
+    pub fn range(&mut self, range: RangeInclusive<u32>) -> u32 {
+        let (start, end) = range.into_inner();
+        start + (self.next_u64() % (u64::from(end - start) + 1)) as u32
+    }
+
+    /// Shuffles `items` in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A generated puzzle input and the answers to both of its parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub input: String,
+    pub part1: String,
+    pub part2: String,
+}
+
+/// Generates an input for `day` from `seed`.  `size` is the number of elves for day 1, rounds for
+/// day 2, groups of three rucksacks for day 3, pairs for day 4, commands for day 5 and characters
+/// for day 6; when it's not given, an input about as large as the real one is generated.
+///
+/// ```rust
+/// # use aoc_2022::generate::*;
+/// let generated = generate(1, 7, Some(10)).unwrap();
+/// assert_eq!(generated, generate(1, 7, Some(10)).unwrap());
+/// assert_eq!(generated.input.split("\n\n").count(), 10);
+/// assert_eq!(
+///     aoc_2022::day1::part1(&generated.input).unwrap().to_string(),
+///     generated.part1
+/// );
+///
+/// assert!(generate(7, 7, None).is_err());
+/// ```
+pub fn generate(day: u32, seed: u64, size: Option<usize>) -> Result<Generated> {
+    match day {
+        1 => day1(seed, size.unwrap_or(250)),
+        2 => day2(seed, size.unwrap_or(2500)),
+        3 => day3(seed, size.unwrap_or(100)),
+        4 => day4(seed, size.unwrap_or(1000)),
+        5 => day5(seed, 9, size.unwrap_or(500)),
+        6 => {
+            let len = size.unwrap_or(4096);
+            if len < 18 {
+                bail!("A day 6 stream needs at least 18 characters to hold both markers");
+            }
+            let mut rng = Rng::new(seed);
+            let packet = 4 + rng.below(len - 17);
+            let message = packet + 14 + rng.below(len - packet - 13);
+            day6(seed, len, packet, message)
+        }
+        _ => bail!("There is no generator for day {day}"),
+    }
+}
+
+/// Generates calorie lists for `elves` elves, each carrying between one and six items.
+pub fn day1(seed: u64, elves: usize) -> Result<Generated> {
+    if elves < 3 {
+        bail!("Part 2 needs at least three elves, but {elves} were asked for");
+    }
+
+    let mut rng = Rng::new(seed);
+    let mut groups = Vec::with_capacity(elves);
+    let mut totals = Vec::with_capacity(elves);
+    for _ in 0..elves {
+        let items: Vec<u32> = (0..rng.range(1..=6))
+            .map(|_| rng.range(1000..=60000))
+            .collect();
+        totals.push(items.iter().sum::<u32>());
+        groups.push(
+            items
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+    }
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+
+    Ok(Generated {
+        input: groups.join("\n\n") + "\n",
+        part1: totals[0].to_string(),
+        part2: totals[..3].iter().sum::<u32>().to_string(),
+    })
+}
+
+/// Generates a strategy guide with `rounds` rounds.
+pub fn day2(seed: u64, rounds: usize) -> Result<Generated> {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    let (mut part1, mut part2) = (0, 0);
+    for _ in 0..rounds {
+        // 0, 1 and 2 are rock, paper and scissors in the first column, and in the second column
+        // are either the same shapes or losing, drawing and winning.
+        let (theirs, second) = (rng.below(3), rng.below(3));
+        input.push(char::from(b'A' + theirs as u8));
+        input.push(' ');
+        input.push(char::from(b'X' + second as u8));
+        input.push('\n');
+
+        let outcome = (second + 4 - theirs) % 3;
+        part1 += second + 1 + 3 * outcome;
+        let ours = (theirs + second + 2) % 3;
+        part2 += ours + 1 + 3 * second;
+    }
+
+    Ok(Generated {
+        input,
+        part1: part1.to_string(),
+        part2: part2.to_string(),
+    })
+}
+
+/// Generates `groups` groups of three rucksacks.  The compartments of each rucksack share exactly
+/// one item, and the rucksacks in each group share exactly one badge.
+pub fn day3(seed: u64, groups: usize) -> Result<Generated> {
+    let priority = |item: u8| match item {
+        b'a'..=b'z' => u32::from(item - b'a') + 1,
+        _ => u32::from(item - b'A') + 27,
+    };
+
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    let (mut part1, mut part2) = (0, 0);
+    for _ in 0..groups {
+        let mut items: Vec<u8> = (b'a'..=b'z').chain(b'A'..=b'Z').collect();
+        rng.shuffle(&mut items);
+        let badge = items[0];
+        part2 += priority(badge);
+
+        // each rucksack leaves out one third of the other items, so nothing but the badge can be
+        // in all three.
+        let pools: Vec<&[u8]> = items[1..].chunks(17).collect();
+        for left_out in 0..3 {
+            let mut allowed: Vec<u8> = (0..3)
+                .filter(|&pool| pool != left_out)
+                .flat_map(|pool| pools[pool].iter().copied())
+                .chain([badge])
+                .collect();
+            rng.shuffle(&mut allowed);
+            let shared = allowed[0];
+            part1 += priority(shared);
+
+            let len = rng.below(15) + 2;
+            for half in allowed[1..].chunks(17) {
+                let mut compartment = vec![shared];
+                if half.contains(&badge) {
+                    compartment.push(badge);
+                }
+                while compartment.len() < len {
+                    compartment.push(half[rng.below(half.len())]);
+                }
+                rng.shuffle(&mut compartment);
+                input.extend(compartment.into_iter().map(char::from));
+            }
+            input.push('\n');
+        }
+    }
+
+    Ok(Generated {
+        input,
+        part1: part1.to_string(),
+        part2: part2.to_string(),
+    })
+}
+
+/// Generates `pairs` pairs of section assignments between 1 and 99.
+pub fn day4(seed: u64, pairs: usize) -> Result<Generated> {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    let (mut part1, mut part2) = (0, 0);
+    for _ in 0..pairs {
+        let mut range = || {
+            let start = rng.range(1..=99);
+            (start, rng.range(start..=99))
+        };
+        let (a, b) = (range(), range());
+        input += &format!("{}-{},{}-{}\n", a.0, a.1, b.0, b.1);
+
+        if (a.0 <= b.0 && b.1 <= a.1) || (b.0 <= a.0 && a.1 <= b.1) {
+            part1 += 1;
+        }
+        if a.0 <= b.1 && b.0 <= a.1 {
+            part2 += 1;
+        }
+    }
+
+    Ok(Generated {
+        input,
+        part1: part1.to_string(),
+        part2: part2.to_string(),
+    })
+}
+
+/// Generates a drawing of `stacks` stacks of up to eight crates followed by `commands` commands,
+/// each of which moves at least one crate from a non-empty stack.
+pub fn day5(seed: u64, stacks: usize, commands: usize) -> Result<Generated> {
+    if !(2..=9).contains(&stacks) {
+        bail!("Stacks are numbered with a single digit and moves need two of them, so there must be 2 to 9 stacks, not {stacks}");
+    }
+
+    let mut rng = Rng::new(seed);
+    let initial: Vec<Vec<char>> = (0..stacks)
+        .map(|_| {
+            (0..rng.range(1..=8))
+                .map(|_| char::from(b'A' + rng.below(26) as u8))
+                .collect()
+        })
+        .collect();
+
+    // the two models move the same number of crates between the same stacks, so either can be
+    // used to decide which moves are possible.
+    let (mut single, mut multiple) = (initial.clone(), initial.clone());
+    let mut moves = Vec::with_capacity(commands);
+    for _ in 0..commands {
+        let non_empty: Vec<usize> = (0..stacks).filter(|&i| !single[i].is_empty()).collect();
+        let from = non_empty[rng.below(non_empty.len())];
+        let to = (from + 1 + rng.below(stacks - 1)) % stacks;
+        let amount = rng.below(single[from].len()) + 1;
+
+        for _ in 0..amount {
+            let c = single[from].pop().expect("amount is at most the height");
+            single[to].push(c);
+        }
+        let split = multiple[from].len() - amount;
+        let moved = multiple[from].split_off(split);
+        multiple[to].extend(moved);
+
+        moves.push(Command {
+            amount,
+            from: from + 1,
+            to: to + 1,
+        });
+    }
+
+    let tops = |stacks: &[Vec<char>]| stacks.iter().filter_map(|s| s.last()).collect();
+    let crane = Crane::new(
+        initial
+            .iter()
+            .map(|stack| stack.iter().copied().map(Crate::from).collect())
+            .collect(),
+        vec![],
+    );
+    let mut input = format!("{crane}\n\n");
+    for command in moves {
+        input += &format!("{command}\n");
+    }
+
+    Ok(Generated {
+        input,
+        part1: tops(&single),
+        part2: tops(&multiple),
+    })
+}
+
+/// Generates a stream of `len` lowercase letters whose first run of four distinct letters ends
+/// after `packet` characters and whose first run of fourteen ends after `message`.  The
+/// start-of-message marker must start after the start-of-packet marker has ended.
+pub fn day6(seed: u64, len: usize, packet: usize, message: usize) -> Result<Generated> {
+    if packet < 4 || message < packet + 14 || len < message {
+        bail!("Can't place markers after {packet} and {message} characters in a stream of {len}");
+    }
+
+    let mut rng = Rng::new(seed);
+    let mut stream = Vec::with_capacity(len);
+    let mut window = Window::default();
+    let mut run = 0;
+    for (end, width) in [(packet, Some(4)), (message, Some(14)), (len, None)] {
+        // fill up to the marker without completing it early, by reusing a letter from the
+        // current run whenever a new one would make it too long.
+        let limit = width.unwrap_or(usize::MAX);
+        while stream.len() < end - width.unwrap_or(0) {
+            let mut letter = b'a' + rng.below(26) as u8;
+            let current = &stream[stream.len() - run..];
+            if !current.contains(&letter) && run + 1 >= limit {
+                letter = current[rng.below(run)];
+            }
+            stream.push(letter);
+            run = window.push(u32::from(letter));
+        }
+        let Some(width) = width else {
+            break;
+        };
+
+        // repeating the last letter starts a new run, which the rest of the marker extends.
+        let mut marker = vec![stream.last().copied().unwrap_or(b'a')];
+        while marker.len() < width {
+            let letter = b'a' + rng.below(26) as u8;
+            if !marker.contains(&letter) {
+                marker.push(letter);
+            }
+        }
+        for letter in marker {
+            stream.push(letter);
+            run = window.push(u32::from(letter));
+        }
+    }
+
+    Ok(Generated {
+        input: String::from_utf8(stream).expect("only ASCII letters are generated"),
+        part1: packet.to_string(),
+        part2: message.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day1, day2, day3, day4, day5::CraneModel, day6};
+
+    #[test]
+    fn test_answers_match_solvers() {
+        for seed in 0..20 {
+            let generated = generate(1, seed, Some(50)).unwrap();
+            assert_eq!(
+                day1::part1(&generated.input).unwrap().to_string(),
+                generated.part1
+            );
+            assert_eq!(
+                day1::part2(&generated.input).unwrap().to_string(),
+                generated.part2
+            );
+
+            let generated = generate(2, seed, Some(50)).unwrap();
+            assert_eq!(day2::part1(&generated.input).to_string(), generated.part1);
+            assert_eq!(day2::part2(&generated.input).to_string(), generated.part2);
+
+            let generated = generate(3, seed, Some(20)).unwrap();
+            assert_eq!(
+                day3::part1(&generated.input).unwrap().to_string(),
+                generated.part1
+            );
+            assert_eq!(
+                day3::part2(&generated.input).unwrap().to_string(),
+                generated.part2
+            );
+
+            let generated = generate(4, seed, Some(50)).unwrap();
+            let part1 = day4::part1(&generated.input).unwrap();
+            assert_eq!(part1.to_string(), generated.part1);
+            let part2 = day4::part2(&generated.input).unwrap();
+            assert_eq!(part2.to_string(), generated.part2);
+
+            let generated = generate(5, seed, Some(50)).unwrap();
+            for (model, answer) in [
+                (CraneModel::CrateMover9000, &generated.part1),
+                (CraneModel::CrateMover9001, &generated.part2),
+            ] {
+                let mut crane = Crane::parse(&generated.input).unwrap();
+                crane.process(model).unwrap();
+                assert_eq!(&crane.read_stack_tops_string(), answer);
+            }
+
+            let generated = generate(6, seed, Some(100)).unwrap();
+            let part1 = day6::part1(&generated.input).unwrap();
+            assert_eq!(part1.to_string(), generated.part1);
+            let part2 = day6::part2(&generated.input).unwrap();
+            assert_eq!(part2.to_string(), generated.part2);
+        }
+    }
+
+    #[test]
+    fn test_day6_markers_at_the_edges() {
+        let generated = day6(3, 18, 4, 18).unwrap();
+        assert_eq!(generated.input.len(), 18);
+        assert_eq!(day6::part1(&generated.input).unwrap(), 4);
+        assert_eq!(day6::part2(&generated.input).unwrap(), 18);
+
+        assert!(day6(3, 30, 10, 20).is_err());
+    }
+}
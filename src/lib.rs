@@ -1,10 +1,31 @@
 use aoc_runner_derive::aoc_lib;
 
+mod chunks;
+pub mod client;
 pub mod day1;
 pub mod day2;
 pub mod day3;
 pub mod day4;
 pub mod day5;
 pub mod day6;
+pub mod generate;
+pub mod minimize;
+pub mod normalize;
+pub mod quiet;
+pub mod runner;
+pub mod scaffold;
+pub mod solvers;
+pub mod submit;
+pub mod validate;
+
+/// Tests generated by `build.rs` from the examples in each day's puzzle description.
+#[cfg(test)]
+mod examples {
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+}
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod reference;
 
 aoc_lib! { year = 2022 }
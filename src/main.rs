@@ -0,0 +1,401 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use aoc_2022::{
+    client::{Client, Source},
+    day5::{
+        animate::{write_frames, Animation, Style},
+        Crane, CraneModel,
+    },
+    generate::generate,
+    minimize::{minimize, Failure},
+    normalize::{changes, normalize_for_day, Policy},
+    runner::{self, Outcome},
+    scaffold::scaffold,
+    solvers::{self, Day},
+    submit::{submit, Verdict},
+    validate::validate,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+
+#[derive(Parser)]
+#[command(about = "Tools for working with the Advent of Code 2022 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replays a day 5 input one command at a time.
+    Animate {
+        /// Path to a day 5 input.
+        input: PathBuf,
+        /// Which crane model to simulate.
+        #[arg(long, value_enum, default_value = "9000")]
+        model: Model,
+        /// Milliseconds to wait between frames.
+        #[arg(long, default_value_t = 250)]
+        delay: u64,
+        /// Stop after this command (1-indexed) until Enter is pressed.
+        #[arg(long)]
+        pause_at: Option<usize>,
+        /// Write every frame to this file instead of animating in the terminal.
+        #[arg(long)]
+        frames: Option<PathBuf>,
+    },
+    /// Downloads puzzle inputs, unless they have been downloaded already.
+    Fetch {
+        /// Which days' inputs to download.
+        #[arg(required = true)]
+        days: Vec<u32>,
+        /// The `session` cookie from a logged in browser.
+        #[arg(long, env = "AOC_SESSION", hide_env_values = true)]
+        session: String,
+        /// Download the inputs again even if they are already on disk.
+        #[arg(long)]
+        force: bool,
+        /// Where to keep the inputs.
+        #[arg(long, default_value = "input/2022")]
+        inputs: PathBuf,
+    },
+    /// Generates a random puzzle input, printing its answers to stderr.
+    Generate {
+        /// Which day's input to generate.
+        day: u32,
+        /// Seed for the random number generator; the same seed always gives the same input.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// How large an input to generate, in the day's natural unit: elves, rounds, groups of
+        /// rucksacks, pairs, commands or characters.
+        #[arg(long)]
+        size: Option<usize>,
+        /// Write the input to this file instead of stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Shrinks an input that makes a solver fail, keeping it in the day's format.
+    Minimize {
+        /// Which day's solver to run.
+        day: u32,
+        /// Path to the failing input.
+        input: PathBuf,
+        /// Which part's solver to run.
+        #[arg(long, default_value_t = 1)]
+        part: u32,
+        /// What counts as failing.
+        #[arg(long, value_enum)]
+        failure: FailureKind,
+        /// The answer the solver should give; any other answer counts as failing.
+        #[arg(long, required_if_eq("failure", "mismatch"))]
+        expected: Option<String>,
+        /// Write the minimized input to this file instead of stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Runs every solver in parallel and prints a table of answers, errors and timings.
+    Run {
+        /// Only run these days.
+        days: Vec<u32>,
+        /// Where the inputs are kept.
+        #[arg(long, default_value = "input/2022")]
+        inputs: PathBuf,
+        /// Give up on a solver after this many seconds.
+        #[arg(long)]
+        timeout: Option<f64>,
+        /// How many solvers to run at once; defaults to the number of CPUs.  Solvers that time out
+        /// keep running in the background, so they don't count towards this.
+        #[arg(long)]
+        threads: Option<usize>,
+    },
+    /// Creates the module, input file and registrations for a new day.
+    NewDay {
+        /// Which day to create.
+        day: u32,
+        /// The puzzle's title.
+        #[arg(long, default_value = "TODO")]
+        title: String,
+        /// The root of the crate.
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// Solves a puzzle and submits the answer, unless it's already known to be wrong.
+    Submit {
+        /// Which day to solve.
+        day: u32,
+        /// Which part to solve.
+        part: u32,
+        /// Submit this answer instead of solving the puzzle.
+        #[arg(long)]
+        answer: Option<String>,
+        /// The `session` cookie from a logged in browser.
+        #[arg(long, env = "AOC_SESSION", hide_env_values = true)]
+        session: String,
+        /// Where the inputs are kept.
+        #[arg(long, default_value = "input/2022")]
+        inputs: PathBuf,
+        /// The file recording every answer submitted so far.
+        #[arg(long, default_value = "input/2022/submissions.txt")]
+        history: PathBuf,
+    },
+    /// Checks that an input is well-formed once normalized, listing every problem found and noting
+    /// what normalization changed.
+    Validate {
+        /// Which day's format to check against.
+        day: u32,
+        /// Path to the input.
+        input: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FailureKind {
+    Panic,
+    Error,
+    Mismatch,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Model {
+    #[value(name = "9000")]
+    CrateMover9000,
+    #[value(name = "9001")]
+    CrateMover9001,
+}
+
+impl From<Model> for CraneModel {
+    fn from(model: Model) -> Self {
+        match model {
+            Model::CrateMover9000 => CraneModel::CrateMover9000,
+            Model::CrateMover9001 => CraneModel::CrateMover9001,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Animate {
+            input,
+            model,
+            delay,
+            pause_at,
+            frames,
+        } => animate(input, model.into(), delay, pause_at, frames),
+        Command::Fetch {
+            days,
+            session,
+            force,
+            inputs,
+        } => {
+            let mut client = Client::new(&session, inputs);
+            for day in days {
+                match client.fetch_input(day, force)? {
+                    (path, Source::Cache) => println!("{} is already there", path.display()),
+                    (path, Source::Server) => println!("wrote {}", path.display()),
+                }
+            }
+            Ok(())
+        }
+        Command::Generate {
+            day,
+            seed,
+            size,
+            output,
+        } => {
+            let generated = generate(day, seed, size)?;
+            match output {
+                Some(path) => fs::write(&path, &generated.input)
+                    .with_context(|| format!("Failed to write {}", path.display()))?,
+                None => io::stdout().write_all(generated.input.as_bytes())?,
+            }
+            eprintln!("part 1: {}", generated.part1);
+            eprintln!("part 2: {}", generated.part2);
+            Ok(())
+        }
+        Command::Minimize {
+            day,
+            input,
+            part,
+            failure,
+            expected,
+            output,
+        } => {
+            let failure = match failure {
+                FailureKind::Panic => Failure::Panic,
+                FailureKind::Error => Failure::Error,
+                FailureKind::Mismatch => Failure::Mismatch(expected.unwrap_or_default()),
+            };
+            run_minimize(day, input, part, failure, output)
+        }
+        Command::NewDay { day, title, root } => {
+            for path in scaffold(&root, day, &title)? {
+                println!("wrote {}", path.display());
+            }
+            Ok(())
+        }
+        Command::Run {
+            days,
+            inputs,
+            timeout,
+            threads,
+        } => run_all(&days, &inputs, timeout, threads),
+        Command::Submit {
+            day,
+            part,
+            answer,
+            session,
+            inputs,
+            history,
+        } => {
+            let mut client = Client::new(&session, inputs);
+            let answer = match answer {
+                Some(answer) => answer,
+                None => {
+                    let solver = solvers::get(day)?.part(part)?;
+                    solver(&read_input(day, &client.input_path(day))?)?
+                }
+            };
+            println!("day {day}, part {part}: {answer}");
+            let verdict = submit(&mut client, &history, day, part, &answer)?;
+            if verdict != Verdict::Correct {
+                bail!("{verdict}");
+            }
+            println!("{verdict}");
+            Ok(())
+        }
+        Command::Validate { day, input } => {
+            let raw = fs::read_to_string(&input)
+                .with_context(|| format!("Failed to read {}", input.display()))?;
+            for change in changes(&raw, Policy::for_day(day)) {
+                println!("{}: note: {change}", input.display());
+            }
+            let findings = validate(day, &normalize_for_day(day, &raw))?;
+            for finding in &findings {
+                println!("{}: {finding}", input.display());
+            }
+            if !findings.is_empty() {
+                bail!("Found {} problems in {}", findings.len(), input.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Reads the input for `day` from `path`, normalized the way the solvers expect.
+fn read_input(day: u32, path: &Path) -> Result<String> {
+    let input =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(normalize_for_day(day, &input))
+}
+
+fn run_all(
+    days: &[u32],
+    inputs: &Path,
+    timeout: Option<f64>,
+    threads: Option<usize>,
+) -> Result<()> {
+    let selected: Vec<Day> = match days {
+        [] => solvers::DAYS.to_vec(),
+        days => days
+            .iter()
+            .map(|&day| solvers::get(day).copied())
+            .collect::<Result<_>>()?,
+    };
+    let timeout = timeout
+        .map(Duration::try_from_secs_f64)
+        .transpose()
+        .context("Invalid timeout")?;
+    let threads = match threads {
+        Some(threads) => threads,
+        None => thread::available_parallelism().map_or(1, usize::from),
+    };
+
+    let (jobs, missing) = runner::jobs(&selected, inputs);
+    for day in missing {
+        eprintln!(
+            "skipping day {day}, which has no input in {}",
+            inputs.display()
+        );
+    }
+
+    let reports = runner::run(jobs, threads, timeout);
+
+    print!("{}", runner::table(&reports));
+    let failed = reports
+        .iter()
+        .filter(|report| !matches!(report.outcome, Outcome::Answer(_)))
+        .count();
+    if failed > 0 {
+        bail!("{failed} of {} solvers failed", reports.len());
+    }
+    Ok(())
+}
+
+fn run_minimize(
+    day: u32,
+    input: PathBuf,
+    part: u32,
+    failure: Failure,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let solver = solvers::get(day)?.part(part)?;
+    let input = read_input(day, &input)?;
+
+    let minimized = minimize(day, &input, |input| failure.check(solver, input))?;
+
+    eprintln!(
+        "shrank {} lines to {}",
+        input.lines().count(),
+        minimized.lines().count()
+    );
+    match output {
+        Some(path) => fs::write(&path, minimized)
+            .with_context(|| format!("Failed to write {}", path.display())),
+        None => Ok(io::stdout().write_all(minimized.as_bytes())?),
+    }
+}
+
+fn animate(
+    input: PathBuf,
+    model: CraneModel,
+    delay: u64,
+    pause_at: Option<usize>,
+    frames: Option<PathBuf>,
+) -> Result<()> {
+    let input = read_input(5, &input)?;
+    let crane = Crane::parse(&input)?;
+    let animation = Animation::new(crane, model);
+
+    if let Some(path) = frames {
+        let file =
+            File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+        return write_frames(animation, BufWriter::new(file))
+            .with_context(|| format!("Failed to write every frame to {}", path.display()));
+    }
+
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    for frame in animation {
+        let frame = frame?;
+        // clear the screen and move the cursor back to the top left before drawing
+        write!(stdout, "\x1b[2J\x1b[H{}", frame.render(Style::Ansi))?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+
+        if frame.index.is_some_and(|i| Some(i + 1) == pause_at) {
+            write!(stdout, "paused, press Enter to continue")?;
+            stdout.flush()?;
+            stdin.read_line(&mut String::new())?;
+        } else {
+            thread::sleep(Duration::from_millis(delay));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+//! Shrinks an input that makes a solver fail down to a small one that still does.
+//!
+//! The input is split into records that can be removed without breaking the day's format (elves'
+//! items, rounds, groups of three rucksacks, pairs, commands below an untouched stack drawing, or
+//! the characters of a stream), and delta debugging removes as many of them as it can while the
+//! failure persists.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use anyhow::{bail, Result};
+
+use crate::{quiet::quietly, solvers::Solver};
+
+/// The kind of failure to preserve while shrinking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// The solver panics.
+    Panic,
+    /// The solver returns an error.
+    Error,
+    /// The solver succeeds, but with an answer other than this one.
+    Mismatch(String),
+}
+
+impl Failure {
+    /// Does running `solver` on `input` fail in this way?  Panics are expected, so their messages
+    /// are kept quiet.
+    pub fn check(&self, solver: Solver, input: &str) -> bool {
+        match panic::catch_unwind(AssertUnwindSafe(|| quietly(|| solver(input)))) {
+            Err(_) => *self == Failure::Panic,
+            Ok(Err(_)) => *self == Failure::Error,
+            Ok(Ok(answer)) => matches!(self, Failure::Mismatch(expected) if answer != *expected),
+        }
+    }
+}
+
+/// An input split into removable records.  Records with the same group are joined with `inner`,
+/// and groups are joined with `outer`, after an untouched `prefix`.
+struct Records<'a> {
+    prefix: &'a str,
+    records: Vec<(usize, &'a str)>,
+    inner: &'static str,
+    outer: &'static str,
+}
+
+impl<'a> Records<'a> {
+    fn split(day: u32, input: &'a str) -> Records<'a> {
+        let lines = |text: &'a str| text.lines().enumerate().collect();
+        match day {
+            1 => Records {
+                prefix: "",
+                records: input
+                    .split("\n\n")
+                    .enumerate()
+                    .flat_map(|(elf, items)| items.lines().map(move |item| (elf, item)))
+                    .collect(),
+                inner: "\n",
+                outer: "\n\n",
+            },
+            3 => {
+                // a rucksack's group depends on its position, so whole groups are removed at a
+                // time.  Any leftover lines at the end form a record of their own.
+                let mut records = vec![];
+                let mut rest = input;
+                while !rest.is_empty() {
+                    let end = rest
+                        .match_indices('\n')
+                        .nth(2)
+                        .map_or(rest.len(), |(i, _)| i);
+                    records.push((records.len(), &rest[..end]));
+                    rest = rest.get(end + 1..).unwrap_or_default();
+                }
+                Records {
+                    prefix: "",
+                    records,
+                    inner: "\n",
+                    outer: "\n",
+                }
+            }
+            5 => match input.find("\n\n") {
+                Some(end) => Records {
+                    prefix: &input[..end + 2],
+                    records: lines(&input[end + 2..]),
+                    inner: "\n",
+                    outer: "\n",
+                },
+                None => Records {
+                    prefix: "",
+                    records: lines(input),
+                    inner: "\n",
+                    outer: "\n",
+                },
+            },
+            6 => Records {
+                prefix: "",
+                records: input
+                    .trim_end()
+                    .char_indices()
+                    .map(|(i, c)| (i, &input[i..i + c.len_utf8()]))
+                    .collect(),
+                inner: "",
+                outer: "",
+            },
+            _ => Records {
+                prefix: "",
+                records: lines(input),
+                inner: "\n",
+                outer: "\n",
+            },
+        }
+    }
+
+    /// Builds the input holding only the records at `keep`, which are in order.
+    fn join(&self, keep: &[usize]) -> String {
+        let mut joined = self.prefix.to_string();
+        let mut group = None;
+        for &i in keep {
+            let (record_group, text) = self.records[i];
+            match group {
+                None => {}
+                Some(group) if group == record_group => joined.push_str(self.inner),
+                Some(_) => joined.push_str(self.outer),
+            }
+            joined.push_str(text);
+            group = Some(record_group);
+        }
+        if !keep.is_empty() && !self.outer.is_empty() {
+            joined.push('\n');
+        }
+
+        joined
+    }
+}
+
+/// Removes as many items from `items` as possible while `failing` still holds, using Zeller's
+/// ddmin: try dropping each of `n` chunks in turn, and split into smaller chunks whenever none
+/// can be dropped.
+fn ddmin(mut items: Vec<usize>, mut failing: impl FnMut(&[usize]) -> bool) -> Vec<usize> {
+    let mut n = 2;
+    while !items.is_empty() {
+        let chunk = items.len().div_ceil(n);
+        let mut reduced = false;
+        for start in (0..items.len()).step_by(chunk) {
+            let complement: Vec<usize> = items[..start]
+                .iter()
+                .chain(&items[(start + chunk).min(items.len())..])
+                .copied()
+                .collect();
+            if failing(&complement) {
+                items = complement;
+                n = (n - 1).max(2);
+                reduced = true;
+                break;
+            }
+        }
+
+        if !reduced {
+            if chunk == 1 {
+                break;
+            }
+            n = (n * 2).min(items.len());
+        }
+    }
+
+    items
+}
+
+/// Shrinks `input` for `day` for as long as `failing` still holds, keeping it in that day's
+/// format.  Fails if the original input doesn't satisfy `failing`.
+///
+/// ```rust
+/// # use aoc_2022::{minimize::*, solvers};
+/// // one elf's list has a typo in it
+/// let input = "1000\n2000\n3000\n\n4000\n\n5000\n6O00\n\n7000\n8000\n9000\n\n10000\n";
+/// let solver = solvers::get(1).unwrap().part1;
+/// let small = minimize(1, input, |input| Failure::Error.check(solver, input)).unwrap();
+/// assert_eq!(small, "6O00\n");
+///
+/// // the last command takes more crates than there are, whatever happened before it
+/// let input = "[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\nmove 2 from 2 to 1\nmove 3 from 1 to 2\n";
+/// let solver = solvers::get(5).unwrap().part1;
+/// let small = minimize(5, input, |input| Failure::Error.check(solver, input)).unwrap();
+/// assert_eq!(small, "[A] [B]\n 1   2 \n\nmove 3 from 1 to 2\n");
+/// ```
+pub fn minimize(day: u32, input: &str, mut failing: impl FnMut(&str) -> bool) -> Result<String> {
+    if !failing(input) {
+        bail!("The input doesn't fail to begin with");
+    }
+
+    let records = Records::split(day, input);
+    let all = (0..records.records.len()).collect();
+    let keep = ddmin(all, |keep| failing(&records.join(keep)));
+    let minimized = records.join(&keep);
+    // the records might not have covered the whole input, so make sure the result still fails.
+    if failing(&minimized) {
+        Ok(minimized)
+    } else {
+        Ok(input.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, solvers};
+
+    #[test]
+    fn test_keeps_failing_group() {
+        let mut input = generate::day3(1, 20).unwrap().input;
+        // a group of three with nothing in common
+        input += "abab\ncdcd\nefef\n";
+        input += &generate::day3(2, 20).unwrap().input;
+
+        let solver = solvers::get(3).unwrap().part2;
+        let small = minimize(3, &input, |input| Failure::Error.check(solver, input)).unwrap();
+        assert_eq!(small, "abab\ncdcd\nefef\n");
+    }
+
+    #[test]
+    fn test_keeps_drawing() {
+        // shrink to commands where the two crane models end up with different tops
+        let day = solvers::get(5).unwrap();
+        let models_differ = |input: &str| match ((day.part1)(input), (day.part2)(input)) {
+            (Ok(part1), Ok(part2)) => part1 != part2,
+            _ => false,
+        };
+        let generated = generate::day5(4, 5, 200).unwrap();
+        let small = minimize(5, &generated.input, models_differ).unwrap();
+
+        let (drawing, commands) = small.split_once("\n\n").unwrap();
+        assert!(generated.input.starts_with(&format!("{drawing}\n\n")));
+        assert!((1..10).contains(&commands.lines().count()));
+        assert!(models_differ(&small));
+    }
+
+    #[test]
+    fn test_panics_and_streams() {
+        let input = generate::day6(9, 500, 100, 300).unwrap().input + "zz";
+        let solver: Solver = |input| {
+            assert!(!input.contains("zz"), "found zz");
+            Ok(String::new())
+        };
+
+        let small = minimize(6, &input, |input| Failure::Panic.check(solver, input));
+        assert_eq!(small.unwrap(), "zz");
+
+        assert!(minimize(6, "abc", |input| Failure::Panic.check(solver, input)).is_err());
+    }
+}
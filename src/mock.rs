@@ -0,0 +1,167 @@
+//! A stand-in for the Advent of Code website, served on a local port so the client can be tested
+//! without a network connection.  It only knows one session cookie, one day always fails, and
+//! another always asks for answers to be submitted later.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use tiny_http::{Header, Request, Response, Server};
+
+/// The only session cookie the mock server accepts.
+pub const SESSION: &str = "53616c7465645f5f-mock";
+
+pub struct MockServer {
+    server: Arc<Server>,
+    requests: Arc<AtomicUsize>,
+    thread: Option<JoinHandle<()>>,
+    dir: PathBuf,
+}
+
+impl MockServer {
+    /// A day whose endpoints always fail with a 500.
+    pub const BROKEN_DAY: u32 = 13;
+    /// A day whose answers are never checked, because one was given too recently.
+    pub const SLOW_DAY: u32 = 20;
+
+    /// Starts a server on a free port, along with a scratch directory that is removed when the
+    /// server is dropped.
+    pub fn start() -> MockServer {
+        static STARTED: AtomicUsize = AtomicUsize::new(0);
+
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let thread = {
+            let server = Arc::clone(&server);
+            let requests = Arc::clone(&requests);
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    requests.fetch_add(1, Ordering::SeqCst);
+                    respond(request);
+                }
+            })
+        };
+
+        let dir = env::temp_dir().join(format!(
+            "aoc-2022-mock-{}-{}",
+            std::process::id(),
+            STARTED.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+
+        MockServer {
+            server,
+            requests,
+            thread: Some(thread),
+            dir,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.server.server_addr())
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// How many requests have been made so far.
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// The input served for `day`.
+    pub fn input(day: u32) -> String {
+        format!("the input for day {day}\n")
+    }
+
+    /// The correct answer to `part` of `day`.
+    pub fn answer(day: u32, part: u32) -> i64 {
+        i64::from(day) * 1000 + i64::from(part)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn respond(mut request: Request) {
+    let logged_in = request.headers().iter().any(|header| {
+        header.field.equiv("Cookie")
+            && header
+                .value
+                .as_str()
+                .split(';')
+                .any(|cookie| cookie.trim() == format!("session={SESSION}"))
+    });
+
+    let day = request
+        .url()
+        .strip_prefix("/2022/day/")
+        .and_then(|rest| rest.split_once('/'))
+        .and_then(|(day, endpoint)| Some((day.parse::<u32>().ok()?, endpoint)))
+        .filter(|&(day, _)| (1..=25).contains(&day));
+
+    let (status, body) = match day {
+        None => (404, "404 Not Found".to_string()),
+        Some((MockServer::BROKEN_DAY, _)) => (500, "Internal Server Error".to_string()),
+        Some(_) if !logged_in => (
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.".to_string(),
+        ),
+        Some((day, "input")) => (200, MockServer::input(day)),
+        Some((day, "answer")) => {
+            let mut form = String::new();
+            let _ = request.as_reader().read_to_string(&mut form);
+            (200, page(answer_verdict(day, &form)))
+        }
+        Some(_) => (404, "404 Not Found".to_string()),
+    };
+
+    let content_type = Header::from_bytes("Content-Type", "text/plain").unwrap();
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(content_type);
+    let _ = request.respond(response);
+}
+
+/// The message the site gives for the answer in `form`.
+fn answer_verdict(day: u32, form: &str) -> &'static str {
+    let field = |name: &str| {
+        form.split('&')
+            .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+    };
+    let Some(part) = field("level").and_then(|level| level.parse().ok()) else {
+        return "You don't seem to be solving the right level.  Did you already complete it?";
+    };
+    if day == MockServer::SLOW_DAY {
+        return "You gave an answer too recently; you have to wait after submitting an answer \
+                before trying again.  You have 1m 5s left to wait.";
+    }
+
+    let expected = MockServer::answer(day, part);
+    match field("answer").and_then(|answer| answer.parse::<i64>().ok()) {
+        Some(answer) if answer == expected => "That's the right answer!",
+        Some(answer) if answer > expected => {
+            "That's not the right answer; your answer is too high."
+        }
+        Some(_) => "That's not the right answer; your answer is too low.",
+        None => "That's not the right answer.  Please wait one minute before trying again.",
+    }
+}
+
+fn page(message: &str) -> String {
+    format!("<html><body><main>\n<article><p>{message}</p></article>\n</main></body></html>")
+}
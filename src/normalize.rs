@@ -0,0 +1,313 @@
+//! Cleans up inputs that have been through other editors or operating systems, so every solver sees
+//! the same text: no byte order mark, `\n` line endings, no stray whitespace and no trailing blank
+//! lines.
+
+use std::fmt;
+
+/// What normalization is allowed to change for a particular day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    /// Strip whitespace from the start of each line.
+    pub trim_start: bool,
+    /// Strip whitespace, including any `\r` left over from a `\r\n` line ending, from the end of
+    /// each line.
+    pub trim_end: bool,
+}
+
+impl Policy {
+    /// The policy for `day`.  Leading spaces position the crates in day 5's drawing, so they have
+    /// to stay; trailing spaces there are only padding and the parser doesn't need them.
+    pub fn for_day(day: u32) -> Policy {
+        Policy {
+            trim_start: day != 5,
+            trim_end: true,
+        }
+    }
+}
+
+/// Normalizes `input` using the given policy.  Line endings are always converted to `\n`, a
+/// leading byte order mark is removed, and so are any blank lines at the end along with the final
+/// newline.
+///
+/// ```rust
+/// # use aoc_2022::normalize::*;
+/// let input = "\u{feff}  1000 \r\n2000\r\n\r\n3000\r\n\r\n";
+/// assert_eq!(normalize(input, Policy::for_day(1)), "1000\n2000\n\n3000");
+///
+/// let input = "    [D]    \r\n[N] [C]    \r\n 1   2 \r\n";
+/// assert_eq!(normalize(input, Policy::for_day(5)), "    [D]\n[N] [C]\n 1   2");
+/// ```
+pub fn normalize(input: &str, policy: Policy) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    let mut normalized = String::with_capacity(input.len());
+    for line in input.split('\n') {
+        let mut line = line.strip_suffix('\r').unwrap_or(line);
+        if policy.trim_start {
+            line = line.trim_start();
+        }
+        if policy.trim_end {
+            line = line.trim_end();
+        }
+        normalized.push_str(line);
+        normalized.push('\n');
+    }
+
+    normalized.truncate(normalized.trim_end_matches('\n').len());
+    normalized
+}
+
+/// Normalizes `input` for `day` using [`Policy::for_day`].
+pub fn normalize_for_day(day: u32, input: &str) -> String {
+    normalize(input, Policy::for_day(day))
+}
+
+/// Something [`normalize`] would change about an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    ByteOrderMark,
+    CrlfLineEndings { lines: usize },
+    LeadingWhitespace { lines: usize },
+    TrailingWhitespace { lines: usize },
+    TrailingBlankLines { lines: usize },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = |lines: usize| match lines {
+            1 => "1 line".to_string(),
+            lines => format!("{lines} lines"),
+        };
+        match *self {
+            Change::ByteOrderMark => write!(f, "a byte order mark at the start is removed"),
+            Change::CrlfLineEndings { lines } => {
+                write!(f, "\\r\\n line endings on {} become \\n", count(lines))
+            }
+            Change::LeadingWhitespace { lines } => {
+                write!(f, "leading whitespace is removed from {}", count(lines))
+            }
+            Change::TrailingWhitespace { lines } => {
+                write!(f, "trailing whitespace is removed from {}", count(lines))
+            }
+            Change::TrailingBlankLines { lines: 1 } => {
+                write!(f, "a blank line at the end is removed")
+            }
+            Change::TrailingBlankLines { lines } => {
+                write!(f, "{lines} blank lines at the end are removed")
+            }
+        }
+    }
+}
+
+/// Lists what [`normalize`] would change about `input`, beyond dropping its final newline.
+///
+/// ```rust
+/// # use aoc_2022::normalize::*;
+/// let input = "\u{feff}1000 \r\n2000\r\n\r\n3000\n\n\n";
+/// assert_eq!(
+///     changes(input, Policy::for_day(1)),
+///     [
+///         Change::ByteOrderMark,
+///         Change::CrlfLineEndings { lines: 3 },
+///         Change::TrailingWhitespace { lines: 1 },
+///         Change::TrailingBlankLines { lines: 2 },
+///     ]
+/// );
+/// assert_eq!(changes("1000\n2000\n", Policy::for_day(1)), []);
+/// ```
+pub fn changes(input: &str, policy: Policy) -> Vec<Change> {
+    let mut changes = vec![];
+    let input = match input.strip_prefix('\u{feff}') {
+        Some(rest) => {
+            changes.push(Change::ByteOrderMark);
+            rest
+        }
+        None => input,
+    };
+
+    let lines: Vec<&str> = input
+        .strip_suffix('\n')
+        .unwrap_or(input)
+        .split('\n')
+        .collect();
+    let crlf = lines.iter().filter(|line| line.ends_with('\r')).count();
+    let lines: Vec<&str> = lines
+        .iter()
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    let leading = lines
+        .iter()
+        .filter(|line| policy.trim_start && line.trim_start() != **line)
+        .count();
+    let trailing = lines
+        .iter()
+        .filter(|line| policy.trim_end && line.trim_end() != **line)
+        .count();
+    let blank = lines
+        .iter()
+        .rev()
+        .take_while(|line| line.is_empty() || (policy.trim_end && line.trim_end().is_empty()))
+        .count();
+
+    for (lines, change) in [
+        (crlf, Change::CrlfLineEndings { lines: crlf }),
+        (leading, Change::LeadingWhitespace { lines: leading }),
+        (trailing, Change::TrailingWhitespace { lines: trailing }),
+        (blank, Change::TrailingBlankLines { lines: blank }),
+    ] {
+        if lines > 0 {
+            changes.push(change);
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers;
+
+    /// The committed puzzle input of each day, looked up by day number.  Days added with `new-day`
+    /// are left out until their input has been pasted in.
+    fn inputs() -> Vec<(u32, String)> {
+        solvers::DAYS
+            .iter()
+            .filter_map(|day| {
+                let input =
+                    std::fs::read_to_string(format!("input/2022/day{}.txt", day.day)).ok()?;
+                (!input.trim().is_empty()).then_some((day.day, input))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mangled_inputs_solve_the_same() {
+        for (day, input) in inputs() {
+            let input = input.as_str();
+            let solvers = solvers::get(day).unwrap();
+            let expected = [
+                (solvers.part1)(input).unwrap(),
+                (solvers.part2)(input).unwrap(),
+            ];
+
+            let crlf = input.replace('\n', "\r\n");
+            let mangled = [
+                crlf.clone(),
+                format!("\u{feff}{input}"),
+                format!("\u{feff}{crlf}"),
+                input.replace('\n', " \t\n") + "\n\n",
+            ];
+            for mangled in mangled {
+                let normalized = normalize_for_day(day, &mangled);
+                let answers = [
+                    (solvers.part1)(&normalized).unwrap(),
+                    (solvers.part2)(&normalized).unwrap(),
+                ];
+                assert_eq!(answers, expected, "day {day}, input {:?}", &mangled[..20]);
+            }
+        }
+
+        // without normalization, the line endings get in the way
+        let crlf = include_str!("../input/2022/day1.txt").replace('\n', "\r\n");
+        assert!(crate::day1::part1(&crlf).is_err());
+    }
+
+    #[test]
+    fn test_cargo_aoc_runners_normalize() {
+        use crate::*;
+        use aoc_runner::{ArcStr, Runner};
+        use std::error::Error;
+
+        type MakeRunner = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+        let runners: [(u32, [MakeRunner; 4]); 6] = [
+            (
+                1,
+                [
+                    Factory::day1_part1,
+                    Factory::day1_part2,
+                    Factory::day1_part1_parallel,
+                    Factory::day1_part2_parallel,
+                ],
+            ),
+            (
+                2,
+                [
+                    Factory::day2_part1,
+                    Factory::day2_part2,
+                    Factory::day2_part1_parallel,
+                    Factory::day2_part2_parallel,
+                ],
+            ),
+            (
+                3,
+                [
+                    Factory::day3_part1,
+                    Factory::day3_part2,
+                    Factory::day3_part1_parallel,
+                    Factory::day3_part2_parallel,
+                ],
+            ),
+            (
+                4,
+                [
+                    Factory::day4_part1,
+                    Factory::day4_part2,
+                    Factory::day4_part1_parallel,
+                    Factory::day4_part2_parallel,
+                ],
+            ),
+            (
+                5,
+                [
+                    Factory::day5_part1,
+                    Factory::day5_part2,
+                    Factory::day5_part1_vec,
+                    Factory::day5_part2_vec,
+                ],
+            ),
+            (
+                6,
+                [
+                    Factory::day6_part1,
+                    Factory::day6_part2,
+                    Factory::day6_part1_hashmap,
+                    Factory::day6_part2_hashmap,
+                ],
+            ),
+        ];
+
+        let inputs = inputs();
+        for (day, runners) in runners {
+            let Some((_, input)) = inputs.iter().find(|(d, _)| *d == day) else {
+                continue;
+            };
+            let solvers = solvers::get(day).unwrap();
+            let expected = [
+                (solvers.part1)(input).unwrap(),
+                (solvers.part2)(input).unwrap(),
+            ];
+            let mangled = format!("\u{feff}{}\r\n", input.replace('\n', "\r\n"));
+            for (i, runner) in runners.into_iter().enumerate() {
+                let answer = runner(ArcStr::from(&mangled))
+                    .unwrap()
+                    .try_run()
+                    .unwrap()
+                    .to_string();
+                assert_eq!(answer, expected[i % 2], "day {day}, runner {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_clean_inputs_only_lose_trailing_whitespace() {
+        for (day, input) in inputs() {
+            let normalized = normalize_for_day(day, &input);
+            let expected: Vec<&str> = input.lines().map(str::trim_end).collect();
+            assert_eq!(
+                normalized.lines().collect::<Vec<_>>(),
+                expected,
+                "day {day}"
+            );
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Keeps the messages of expected panics quiet without touching anyone else's.
+//!
+//! Swapping the panic hook out around a call races with any other thread doing the same, and
+//! silences unrelated panics on other threads in the meantime.  Instead, one hook is installed the
+//! first time it's needed, and it only skips the message for panics on threads that asked for
+//! quiet.
+
+use std::{cell::Cell, panic, sync::Once};
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with the messages of any panics on this thread left out.  Threads spawned by `f`
+/// aren't covered, so they need to call this themselves.
+pub fn quietly<R>(f: impl FnOnce() -> R) -> R {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.try_with(Cell::get).unwrap_or(false) {
+                hook(info);
+            }
+        }));
+    });
+
+    /// Puts the flag back as it was, even if `f` panics.
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            QUIET.with(|quiet| quiet.set(self.0));
+        }
+    }
+
+    let _restore = Restore(QUIET.with(|quiet| quiet.replace(true)));
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_only_this_thread_is_quiet() {
+        let result = panic::catch_unwind(|| quietly(|| panic!("expected")));
+        assert!(result.is_err());
+        assert!(!QUIET.with(Cell::get));
+
+        quietly(|| {
+            assert!(QUIET.with(Cell::get));
+            quietly(|| {});
+            assert!(QUIET.with(Cell::get));
+            let other = thread::spawn(|| QUIET.with(Cell::get)).join().unwrap();
+            assert!(!other);
+        });
+        assert!(!QUIET.with(Cell::get));
+    }
+}
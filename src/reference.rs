@@ -0,0 +1,342 @@
+//! Slow but obviously correct solutions to every day, used to check the real solvers against random
+//! inputs.  They parse the input as directly as possible and do exactly what the puzzle text
+//! describes, with no attention paid to speed.
+
+use std::collections::HashSet;
+
+pub fn day1(input: &str) -> (u32, u32) {
+    let mut totals: Vec<u32> = input
+        .split("\n\n")
+        .map(|elf| elf.lines().map(|line| line.parse::<u32>().unwrap()).sum())
+        .collect();
+    totals.sort();
+    totals.reverse();
+    (totals[0], totals[0] + totals[1] + totals[2])
+}
+
+pub fn day2(input: &str) -> (u64, u64) {
+    let (mut part1, mut part2) = (0, 0);
+    for line in input.lines() {
+        part1 += match line {
+            "A X" => 1 + 3,
+            "A Y" => 2 + 6,
+            "A Z" => 3,
+            "B X" => 1,
+            "B Y" => 2 + 3,
+            "B Z" => 3 + 6,
+            "C X" => 1 + 6,
+            "C Y" => 2,
+            "C Z" => 3 + 3,
+            _ => panic!("bad round {line}"),
+        };
+        part2 += match line {
+            "A X" => 3,
+            "A Y" => 1 + 3,
+            "A Z" => 2 + 6,
+            "B X" => 1,
+            "B Y" => 2 + 3,
+            "B Z" => 3 + 6,
+            "C X" => 2,
+            "C Y" => 3 + 3,
+            "C Z" => 1 + 6,
+            _ => panic!("bad round {line}"),
+        };
+    }
+    (part1, part2)
+}
+
+pub fn day3(input: &str) -> (u32, u32) {
+    let priority = |item: char| {
+        "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"
+            .find(item)
+            .unwrap() as u32
+            + 1
+    };
+
+    let lines: Vec<&str> = input.lines().collect();
+    let mut part1 = 0;
+    for line in &lines {
+        let (first, second) = line.split_at(line.len() / 2);
+        let shared = first.chars().find(|&c| second.contains(c)).unwrap();
+        part1 += priority(shared);
+    }
+    let mut part2 = 0;
+    for group in lines.chunks(3) {
+        let badge = group[0]
+            .chars()
+            .find(|&c| group[1].contains(c) && group[2].contains(c))
+            .unwrap();
+        part2 += priority(badge);
+    }
+    (part1, part2)
+}
+
+pub fn day4(input: &str) -> (u32, u32) {
+    let (mut part1, mut part2) = (0, 0);
+    for line in input.lines() {
+        let sections: Vec<HashSet<u32>> = line
+            .split(',')
+            .map(|range| {
+                let (start, end) = range.split_once('-').unwrap();
+                (start.parse().unwrap()..=end.parse().unwrap()).collect()
+            })
+            .collect();
+        if sections[0].is_subset(&sections[1]) || sections[1].is_subset(&sections[0]) {
+            part1 += 1;
+        }
+        if !sections[0].is_disjoint(&sections[1]) {
+            part2 += 1;
+        }
+    }
+    (part1, part2)
+}
+
+pub fn day5(input: &str) -> (String, String) {
+    let (drawing, commands) = input.split_once("\n\n").unwrap();
+    let mut rows: Vec<Vec<char>> = drawing.lines().map(|l| l.chars().collect()).collect();
+    let footer = rows.pop().unwrap();
+    let count = footer.iter().filter(|c| c.is_ascii_digit()).count();
+    let mut stacks = vec![vec![]; count];
+    for row in rows.iter().rev() {
+        for (i, stack) in stacks.iter_mut().enumerate() {
+            match row.get(1 + 4 * i) {
+                Some(&c) if c != ' ' => stack.push(c),
+                _ => {}
+            }
+        }
+    }
+
+    let (mut single, mut multiple) = (stacks.clone(), stacks);
+    for line in commands.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let amount: usize = words[1].parse().unwrap();
+        let from = words[3].parse::<usize>().unwrap() - 1;
+        let to = words[5].parse::<usize>().unwrap() - 1;
+
+        for _ in 0..amount {
+            let c = single[from].pop().unwrap();
+            single[to].push(c);
+        }
+        let mut held = vec![];
+        for _ in 0..amount {
+            held.push(multiple[from].pop().unwrap());
+        }
+        while let Some(c) = held.pop() {
+            multiple[to].push(c);
+        }
+    }
+
+    let tops = |stacks: Vec<Vec<char>>| stacks.iter().filter_map(|s| s.last()).collect();
+    (tops(single), tops(multiple))
+}
+
+/// The number of characters read when the first `n` distinct ones have been seen, if ever.
+pub fn day6(input: &str, n: usize) -> Option<usize> {
+    let chars: Vec<char> = input.chars().collect();
+    (n..=chars.len()).find(|&end| chars[end - n..end].iter().collect::<HashSet<_>>().len() == n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        day1, day2, day3, day4,
+        day5::{Crane, CraneModel},
+        day6, generate, validate,
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_puzzle_inputs() {
+        let input = include_str!("../input/2022/day1.txt");
+        assert_eq!(
+            day1(input),
+            (day1::part1(input).unwrap(), day1::part2(input).unwrap())
+        );
+        let input = include_str!("../input/2022/day2.txt");
+        assert_eq!(day2(input), (day2::part1(input), day2::part2(input)));
+        let input = include_str!("../input/2022/day3.txt");
+        assert_eq!(
+            day3(input),
+            (day3::part1(input).unwrap(), day3::part2(input).unwrap())
+        );
+        let input = include_str!("../input/2022/day4.txt");
+        let parts = (day4::part1(input).unwrap(), day4::part2(input).unwrap());
+        assert_eq!(day4(input), parts);
+        let input = include_str!("../input/2022/day5.txt");
+        assert_eq!(day5(input), ("QMBMJDFTD".into(), "NBTVTJNFJ".into()));
+        let input = include_str!("../input/2022/day6.txt");
+        assert_eq!(day6(input, 4), Some(day6::part1(input).unwrap() as usize));
+        assert_eq!(day6(input, 14), Some(day6::part2(input).unwrap() as usize));
+    }
+
+    /// One group of rucksacks written straight from the puzzle's rules, without going through
+    /// [`generate`]: each rucksack has exactly one item type in both compartments, and exactly
+    /// one item type is in all three.  Apart from the badge, no two rucksacks share a letter.
+    fn day3_group() -> impl Strategy<Value = String> {
+        let letters: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+        let rucksack = (
+            1..12usize,
+            prop::collection::vec(0..8usize, 24),
+            any::<bool>(),
+        );
+        (
+            Just(letters).prop_shuffle(),
+            prop::collection::vec(rucksack, 3),
+        )
+            .prop_map(|(letters, rucksacks)| {
+                let badge = letters[0];
+                let lines: Vec<String> = rucksacks
+                    .into_iter()
+                    .zip(letters[1..].chunks(17))
+                    .map(|((len, picks, badge_shared), pool)| {
+                        let shared = if badge_shared || len == 1 {
+                            badge
+                        } else {
+                            pool[0]
+                        };
+                        let mut halves: Vec<Vec<char>> = [&pool[1..9], &pool[9..17]]
+                            .into_iter()
+                            .zip(picks.chunks(12))
+                            .map(|(items, picks)| picks[..len].iter().map(|&i| items[i]).collect())
+                            .collect();
+                        halves[0][0] = shared;
+                        halves[1][len - 1] = shared;
+                        if shared != badge {
+                            halves[1][0] = badge;
+                        }
+                        halves.concat().into_iter().collect()
+                    })
+                    .collect();
+                lines.join("\n")
+            })
+    }
+
+    /// A drawing of single-letter crates lined up over their stack numbers, then commands that
+    /// only ever take crates that are there, written straight from the puzzle's format.
+    fn day5_input() -> impl Strategy<Value = String> {
+        let stacks = prop::collection::vec(prop::collection::vec(b'A'..=b'Z', 0..8), 2..10);
+        let commands = prop::collection::vec(any::<(usize, usize, usize)>(), 0..40);
+        (stacks, commands).prop_map(|(stacks, commands)| {
+            let mut input = String::new();
+            let tallest = stacks.iter().map(Vec::len).max().unwrap_or(0);
+            for height in (0..tallest).rev() {
+                let row: Vec<String> = stacks
+                    .iter()
+                    .map(|stack| match stack.get(height) {
+                        Some(&c) => format!("[{}]", char::from(c)),
+                        None => "   ".to_string(),
+                    })
+                    .collect();
+                input += &row.join(" ");
+                input += "\n";
+            }
+            let numbers: Vec<String> = (1..=stacks.len()).map(|n| format!(" {n} ")).collect();
+            input += &numbers.join(" ");
+            input += "\n\n";
+
+            let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+            for (amount, from, to) in commands {
+                let full: Vec<usize> = (0..heights.len()).filter(|&i| heights[i] > 0).collect();
+                if full.is_empty() {
+                    break;
+                }
+                let from = full[from % full.len()];
+                let to = (from + 1 + to % (heights.len() - 1)) % heights.len();
+                let amount = 1 + amount % heights[from];
+                heights[from] -= amount;
+                heights[to] += amount;
+                input += &format!("move {amount} from {} to {}\n", from + 1, to + 1);
+            }
+            input
+        })
+    }
+
+    /// Checks every way of running the crane against [`day5`].
+    fn check_day5(input: &str) -> Result<(), TestCaseError> {
+        let (part1, part2) = day5(input);
+        for (model, expected) in [
+            (CraneModel::CrateMover9000, &part1),
+            (CraneModel::CrateMover9001, &part2),
+        ] {
+            let mut crane = Crane::parse(input).unwrap();
+            crane.process(model).unwrap();
+            prop_assert_eq!(&crane.read_stack_tops_string(), expected);
+
+            let mut crane = Crane::parse(input).unwrap();
+            crane.process_runs(model).unwrap();
+            prop_assert_eq!(&crane.read_stack_tops_string(), expected);
+
+            let mut crane = Crane::parse(input).unwrap();
+            crane.process_original(model).unwrap();
+            prop_assert_eq!(&crane.read_stack_tops_string(), expected);
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn day1_matches(
+            elves in prop::collection::vec(prop::collection::vec(0..100_000u32, 1..5), 3..30)
+        ) {
+            let input = elves
+                .iter()
+                .map(|elf| elf.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            prop_assert_eq!(day1(&input), (day1::part1(&input).unwrap(), day1::part2(&input).unwrap()));
+        }
+
+        #[test]
+        fn day2_matches(rounds in prop::collection::vec("[ABC] [XYZ]", 0..50)) {
+            let input = rounds.join("\n");
+            prop_assert_eq!(day2(&input), (day2::part1(&input), day2::part2(&input)));
+        }
+
+        #[test]
+        fn day3_matches(seed in any::<u64>(), groups in 0..20usize) {
+            let input = generate::day3(seed, groups).unwrap().input;
+            prop_assert_eq!(day3(&input), (day3::part1(&input).unwrap(), day3::part2(&input).unwrap()));
+        }
+
+        #[test]
+        fn day3_matches_drawn(groups in prop::collection::vec(day3_group(), 0..10)) {
+            let input = groups.join("\n");
+            prop_assert_eq!(validate::day3(&input), vec![]);
+            prop_assert_eq!(day3(&input), (day3::part1(&input).unwrap(), day3::part2(&input).unwrap()));
+        }
+
+        #[test]
+        fn day4_matches(
+            pairs in prop::collection::vec((1..20u32, 0..10u32, 1..20u32, 0..10u32), 0..50)
+        ) {
+            let input = pairs
+                .iter()
+                .map(|(a, a_len, b, b_len)| format!("{a}-{},{b}-{}", a + a_len, b + b_len))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let parts = (day4::part1(&input).unwrap(), day4::part2(&input).unwrap());
+            prop_assert_eq!(day4(&input), parts);
+        }
+
+        #[test]
+        fn day5_matches(seed in any::<u64>(), stacks in 2..10usize, commands in 0..50usize) {
+            check_day5(&generate::day5(seed, stacks, commands).unwrap().input)?;
+        }
+
+        #[test]
+        fn day5_matches_drawn(input in day5_input()) {
+            prop_assert_eq!(validate::day5(&input), vec![]);
+            check_day5(&input)?;
+        }
+
+        #[test]
+        fn day6_matches(input in "[a-p]{0,100}") {
+            for (n, found) in [(4, day6::part1(&input)), (14, day6::part2(&input))] {
+                let found = found.ok().map(|position| position as usize);
+                prop_assert_eq!(day6(&input, n), found);
+                prop_assert_eq!(day6(&input, n), day6::find_marker(&input, n));
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Runs many solvers at once on a pool of threads, so that one slow, failing or panicking solver
+//! doesn't hold up or abort the rest.
+//!
+//! Every solver runs on a thread of its own, watched by one of the pool's workers.  A solver that
+//! is still going when its time is up can't be stopped, so it's left to finish in the background
+//! and its answer is ignored.
+
+use std::{
+    fmt::Write,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    normalize::normalize_for_day,
+    quiet::quietly,
+    solvers::{Day, Solver},
+};
+
+/// One solver to run on one input.
+#[derive(Clone)]
+pub struct Job {
+    pub day: u32,
+    pub part: u32,
+    pub solver: Solver,
+    pub input: Arc<str>,
+}
+
+/// How a solver finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answer(String),
+    Error(String),
+    Panic(String),
+    Timeout,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub day: u32,
+    pub part: u32,
+    pub outcome: Outcome,
+    /// How long the solver ran for, or how long it was given if it timed out.
+    pub duration: Duration,
+}
+
+/// Both parts of every day in `days`, on their inputs in `inputs`.  Days without an input file get
+/// no jobs, and are returned separately.
+pub fn jobs(days: &[Day], inputs: &Path) -> (Vec<Job>, Vec<u32>) {
+    let mut jobs = vec![];
+    let mut missing = vec![];
+    for day in days {
+        let Ok(input) = std::fs::read_to_string(inputs.join(format!("day{}.txt", day.day))) else {
+            missing.push(day.day);
+            continue;
+        };
+        let input: Arc<str> = normalize_for_day(day.day, &input).into();
+        for (part, solver) in [(1, day.part1), (2, day.part2)] {
+            jobs.push(Job {
+                day: day.day,
+                part,
+                solver,
+                input: Arc::clone(&input),
+            });
+        }
+    }
+    (jobs, missing)
+}
+
+/// Runs every job on `threads` threads, giving each solver at most `timeout` if set, and reports
+/// on them in day and part order.
+///
+/// A solver that times out can't be stopped, so it carries on in the background while its worker
+/// moves on to the next job.  `threads` only limits how many solvers are being waited on: after
+/// timeouts, more than that many can be running at once until the process exits.
+pub fn run(jobs: Vec<Job>, threads: usize, timeout: Option<Duration>) -> Vec<Report> {
+    let queue = Arc::new(Mutex::new(jobs.into_iter()));
+    let (send, receive) = mpsc::channel();
+
+    let workers: Vec<_> = (0..threads.max(1))
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let send = send.clone();
+            thread::spawn(move || loop {
+                let Some(job) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                let report = run_job(job, timeout);
+                if send.send(report).is_err() {
+                    break;
+                }
+            })
+        })
+        .collect();
+    drop(send);
+
+    let mut reports: Vec<Report> = receive.iter().collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    reports.sort_by_key(|report| (report.day, report.part));
+    reports
+}
+
+fn run_job(job: Job, timeout: Option<Duration>) -> Report {
+    let (send, receive) = mpsc::channel();
+    let Job {
+        day,
+        part,
+        solver,
+        input,
+    } = job;
+
+    let start = Instant::now();
+    thread::spawn(move || {
+        // panics are reported as outcomes, so their messages are kept quiet
+        let result = panic::catch_unwind(AssertUnwindSafe(|| quietly(|| solver(&input))));
+        let _ = send.send((result, start.elapsed()));
+    });
+
+    let received = match timeout {
+        Some(timeout) => receive.recv_timeout(timeout).ok(),
+        None => receive.recv().ok(),
+    };
+    let (outcome, duration) = match received {
+        Some((Ok(Ok(answer)), duration)) => (Outcome::Answer(answer), duration),
+        Some((Ok(Err(e)), duration)) => (Outcome::Error(format!("{e:#}")), duration),
+        Some((Err(payload), duration)) => {
+            let message = match payload.downcast::<String>() {
+                Ok(message) => *message,
+                Err(payload) => match payload.downcast::<&str>() {
+                    Ok(message) => message.to_string(),
+                    Err(_) => "unknown panic".to_string(),
+                },
+            };
+            (Outcome::Panic(message), duration)
+        }
+        None => (Outcome::Timeout, timeout.unwrap_or_default()),
+    };
+
+    Report {
+        day,
+        part,
+        outcome,
+        duration,
+    }
+}
+
+/// Lays the reports out as a table, one solver to a line.
+///
+/// ```rust
+/// # use aoc_2022::runner::*;
+/// # use std::time::Duration;
+/// let reports = [
+///     Report { day: 1, part: 1, outcome: Outcome::Answer("24000".into()), duration: Duration::from_micros(15) },
+///     Report { day: 1, part: 2, outcome: Outcome::Timeout, duration: Duration::from_secs(1) },
+/// ];
+/// let table = table(&reports);
+/// let lines: Vec<&str> = table.lines().collect();
+/// assert_eq!(lines[0], "day  part       time  result");
+/// assert_eq!(lines[1], "  1     1    15.00µs  24000");
+/// assert_eq!(lines[2], "  1     2      1.00s  timed out");
+/// ```
+pub fn table(reports: &[Report]) -> String {
+    let mut table = format!("{:>3}  {:>4}  {:>9}  result\n", "day", "part", "time");
+    for report in reports {
+        let result = match &report.outcome {
+            Outcome::Answer(answer) => answer.clone(),
+            Outcome::Error(e) => format!("error: {e}"),
+            Outcome::Panic(message) => format!("panicked: {message}"),
+            Outcome::Timeout => "timed out".to_string(),
+        };
+        let time = format!("{:.2?}", report.duration);
+        writeln!(
+            table,
+            "{:>3}  {:>4}  {time:>9}  {result}",
+            report.day, report.part
+        )
+        .unwrap();
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers;
+    use anyhow::bail;
+
+    fn job(day: u32, solver: Solver) -> Job {
+        Job {
+            day,
+            part: 1,
+            solver,
+            input: "".into(),
+        }
+    }
+
+    #[test]
+    fn test_isolation() {
+        let jobs = vec![
+            job(1, |_| Ok("fine".to_string())),
+            job(2, |_| bail!("Bad input")),
+            job(3, |_| unreachable!("bad line")),
+            job(4, |_| {
+                thread::sleep(Duration::from_secs(2));
+                Ok("too late".to_string())
+            }),
+            job(5, |_| Ok("also fine".to_string())),
+        ];
+
+        let start = Instant::now();
+        let reports = run(jobs, 2, Some(Duration::from_millis(200)));
+        let elapsed = start.elapsed();
+
+        let outcomes: Vec<_> = reports.iter().map(|r| (r.day, r.outcome.clone())).collect();
+        assert_eq!(
+            outcomes,
+            [
+                (1, Outcome::Answer("fine".into())),
+                (2, Outcome::Error("Bad input".into())),
+                (
+                    3,
+                    Outcome::Panic("internal error: entered unreachable code: bad line".into())
+                ),
+                (4, Outcome::Timeout),
+                (5, Outcome::Answer("also fine".into())),
+            ]
+        );
+        assert!(elapsed < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_puzzle_inputs() {
+        // days added with `new-day` have an empty input until it's pasted in, and no answer yet
+        let (jobs, _) = jobs(solvers::DAYS, Path::new("input/2022"));
+        let jobs: Vec<Job> = jobs
+            .into_iter()
+            .filter(|job| !job.input.trim().is_empty())
+            .collect();
+        let count = jobs.len();
+        let reports = run(jobs, 4, None);
+        assert_eq!(reports.len(), count);
+
+        let outcome = |day, part| {
+            reports
+                .iter()
+                .find(|report| (report.day, report.part) == (day, part))
+                .map(|report| &report.outcome)
+        };
+        assert_eq!(outcome(5, 1), Some(&Outcome::Answer("QMBMJDFTD".into())));
+        assert_eq!(outcome(5, 2), Some(&Outcome::Answer("NBTVTJNFJ".into())));
+        assert!(reports
+            .iter()
+            .all(|report| matches!(report.outcome, Outcome::Answer(_))));
+    }
+}
@@ -0,0 +1,175 @@
+//! Sets up the files for a new day: the module with stub solvers, its registration in `lib.rs` and
+//! [`solvers::DAYS`](crate::solvers::DAYS), and an empty input file to paste the puzzle input into.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+
+/// The new day's module.  `{day}` and `{title}` are filled in.
+const TEMPLATE: &str = r#"//! --- Day {day}: {title} ---
+//!
+//! Paste the puzzle description here; its sample input and answer become a test.
+
+use anyhow::{bail, Result};
+use aoc_runner_derive::{aoc, aoc_generator};
+
+use crate::normalize::normalize_for_day;
+
+/// Normalizes the input before the cargo-aoc runner hands it to any of the solvers below.
+#[aoc_generator(day{day})]
+fn generator(input: &str) -> String {
+    normalize_for_day({day}, input)
+}
+
+/// Computes the solution to day {day}, part 1 with the given input. Example usage:
+///
+/// ```rust,ignore
+/// # use aoc_2022::day{day}::*;
+/// let input = "";
+/// assert_eq!(part1(input).unwrap(), 0);
+/// ```
+#[aoc(day{day}, part1)]
+pub fn part1(_input: &str) -> Result<u32> {
+    bail!("Day {day}, part 1 hasn't been solved yet")
+}
+
+/// Computes the solution to day {day}, part 2 with the given input. Example usage:
+///
+/// ```rust,ignore
+/// # use aoc_2022::day{day}::*;
+/// let input = "";
+/// assert_eq!(part2(input).unwrap(), 0);
+/// ```
+#[aoc(day{day}, part2)]
+pub fn part2(_input: &str) -> Result<u32> {
+    bail!("Day {day}, part 2 hasn't been solved yet")
+}
+"#;
+
+/// Inserts `line` into the run of lines for which `day_of` gives a day, keeping the run in day
+/// order.  Fails if `day` is already there.
+fn insert_line(
+    source: &str,
+    day: u32,
+    line: &str,
+    day_of: impl Fn(&str) -> Option<u32>,
+) -> Result<String> {
+    let mut lines: Vec<&str> = source.lines().collect();
+    let days: Vec<(usize, u32)> = (0..lines.len())
+        .filter_map(|i| Some((i, day_of(lines[i])?)))
+        .collect();
+    if days.iter().any(|&(_, d)| d == day) {
+        bail!("Day {day} is already registered");
+    }
+    let Some(&(last, _)) = days.last() else {
+        bail!("Couldn't find where the days are listed");
+    };
+
+    let at = days
+        .iter()
+        .find(|&&(_, d)| d > day)
+        .map_or(last + 1, |&(i, _)| i);
+    lines.insert(at, line);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Creates the files for `day` in the crate at `root`, titled `title`, returning the paths written.
+/// Nothing is written if any of the new files already exist or the day is already registered.
+pub fn scaffold(root: &Path, day: u32, title: &str) -> Result<Vec<PathBuf>> {
+    let module = root.join(format!("src/day{day}.rs"));
+    let input = root.join(format!("input/2022/day{day}.txt"));
+    for path in [&module, &input] {
+        if path.exists() {
+            bail!("{} already exists", path.display());
+        }
+    }
+
+    let read = |path: &Path| {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+    };
+    let lib_path = root.join("src/lib.rs");
+    let lib = insert_line(
+        &read(&lib_path)?,
+        day,
+        &format!("pub mod day{day};"),
+        |line| {
+            line.strip_prefix("pub mod day")?
+                .strip_suffix(';')?
+                .parse()
+                .ok()
+        },
+    )?;
+    let solvers_path = root.join("src/solvers.rs");
+    let solvers = insert_line(
+        &read(&solvers_path)?,
+        day,
+        &format!("    {day} => day{day},"),
+        |line| line.trim().split_once(" => day")?.0.parse().ok(),
+    )?;
+
+    let day = day.to_string();
+    let files = [
+        (
+            module,
+            TEMPLATE.replace("{day}", &day).replace("{title}", title),
+        ),
+        (input, String::new()),
+        (lib_path, lib),
+        (solvers_path, solvers),
+    ];
+    for (path, contents) in &files {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_scaffold() {
+        let root = env::temp_dir().join(format!("aoc-2022-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        let lib =
+            "use aoc_runner_derive::aoc_lib;\n\npub mod day1;\npub mod day6;\npub mod solvers;\n";
+        fs::write(root.join("src/lib.rs"), lib).unwrap();
+        let solvers = "days! {\n    1 => day1,\n    6 => day6,\n}\n";
+        fs::write(root.join("src/solvers.rs"), solvers).unwrap();
+
+        let written = scaffold(&root, 8, "Treetop Tree House").unwrap();
+        assert_eq!(written.len(), 4);
+        let module = fs::read_to_string(root.join("src/day8.rs")).unwrap();
+        assert!(module.starts_with("//! --- Day 8: Treetop Tree House ---\n"));
+        assert!(module.contains("#[aoc(day8, part2)]"));
+        assert_eq!(
+            fs::read_to_string(root.join("input/2022/day8.txt")).unwrap(),
+            ""
+        );
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(lib.contains("pub mod day6;\npub mod day8;\n"));
+        let solvers = fs::read_to_string(root.join("src/solvers.rs")).unwrap();
+        assert!(solvers.contains("    6 => day6,\n    8 => day8,\n}"));
+
+        // days in between go in order, and nothing is overwritten
+        scaffold(&root, 7, "No Space Left On Device").unwrap();
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(lib.contains("pub mod day6;\npub mod day7;\npub mod day8;\n"));
+        assert!(scaffold(&root, 8, "Again").is_err());
+        fs::remove_file(root.join("src/day8.rs")).unwrap();
+        fs::remove_file(root.join("input/2022/day8.txt")).unwrap();
+        let err = scaffold(&root, 8, "Again").unwrap_err();
+        assert_eq!(err.to_string(), "Day 8 is already registered");
+        assert!(!root.join("src/day8.rs").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+//! Every day's solvers in one place, with their answers turned into strings so that tools can run
+//! any of them without knowing what type each one returns.
+
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+/// A solver for one part of a day's puzzle.
+pub type Solver = fn(&str) -> Result<String>;
+
+/// Anything a solver can return.
+pub trait Answer {
+    fn into_answer(self) -> Result<String>;
+}
+
+impl Answer for u32 {
+    fn into_answer(self) -> Result<String> {
+        Ok(self.to_string())
+    }
+}
+
+impl Answer for u64 {
+    fn into_answer(self) -> Result<String> {
+        Ok(self.to_string())
+    }
+}
+
+impl Answer for String {
+    fn into_answer(self) -> Result<String> {
+        Ok(self)
+    }
+}
+
+impl<T: Answer> Answer for Result<T> {
+    fn into_answer(self) -> Result<String> {
+        self?.into_answer()
+    }
+}
+
+/// Both solvers for one day.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub day: u32,
+    pub part1: Solver,
+    pub part2: Solver,
+}
+
+impl Day {
+    /// The solver for `part`, which must be 1 or 2.
+    pub fn part(&self, part: u32) -> Result<Solver> {
+        match part {
+            1 => Ok(self.part1),
+            2 => Ok(self.part2),
+            _ => bail!("There is no part {part}, only parts 1 and 2"),
+        }
+    }
+}
+
+impl fmt::Debug for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Day").field("day", &self.day).finish()
+    }
+}
+
+macro_rules! days {
+    ($($day:literal => $module:ident),* $(,)?) => {
+        /// Every day that has been solved, in order.
+        pub const DAYS: &[Day] = &[$(
+            Day {
+                day: $day,
+                part1: |input| crate::$module::part1(input).into_answer(),
+                part2: |input| crate::$module::part2(input).into_answer(),
+            },
+        )*];
+    };
+}
+
+days! {
+    1 => day1,
+    2 => day2,
+    3 => day3,
+    4 => day4,
+    5 => day5,
+    6 => day6,
+}
+
+/// Looks up the solvers for `day`.
+///
+/// ```rust
+/// # use aoc_2022::solvers::*;
+/// let day = get(2).unwrap();
+/// assert_eq!((day.part1)("A Y\nB X\nC Z").unwrap(), "15");
+/// assert_eq!((day.part(2).unwrap())("A Y\nB X\nC Z").unwrap(), "12");
+/// assert!(get(26).is_err());
+/// ```
+pub fn get(day: u32) -> Result<&'static Day> {
+    match DAYS.iter().find(|d| d.day == day) {
+        Some(found) => Ok(found),
+        None => bail!("Day {day} hasn't been solved"),
+    }
+}
@@ -0,0 +1,406 @@
+//! Submitting answers, and remembering what the site said about them so that answers already known
+//! to be wrong are never sent twice.
+//!
+//! The history is a plain text file with one submission per line: the day, the part, the verdict
+//! and the answer, separated by tabs.  Too high and too low verdicts also bound the answers worth
+//! trying, for answers that are numbers.
+
+use std::{fmt, fs, path::Path, time::Duration};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::client::Client;
+
+/// What the site said about an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// A wrong answer was given too recently, and this long is left before trying again.  The
+    /// answer wasn't checked.
+    Wait(Duration),
+    /// This part has been solved already, so the answer wasn't checked.
+    AlreadySolved,
+}
+
+impl Verdict {
+    /// Reads the verdict out of the page returned after submitting an answer.
+    ///
+    /// ```rust
+    /// # use aoc_2022::submit::Verdict;
+    /// # use std::time::Duration;
+    /// let page = "<article><p>That's not the right answer; your answer is too high.</p></article>";
+    /// assert_eq!(Verdict::parse(page).unwrap(), Verdict::TooHigh);
+    /// let page = "<p>You gave an answer too recently. You have 1m 5s left to wait.</p>";
+    /// assert_eq!(Verdict::parse(page).unwrap(), Verdict::Wait(Duration::from_secs(65)));
+    /// ```
+    pub fn parse(page: &str) -> Result<Verdict> {
+        let text = match (page.find("<article>"), page.find("</article>")) {
+            (Some(start), Some(end)) if start < end => &page[start..end],
+            _ => page,
+        };
+
+        if text.contains("That's the right answer") {
+            Ok(Verdict::Correct)
+        } else if text.contains("too high") {
+            Ok(Verdict::TooHigh)
+        } else if text.contains("too low") {
+            Ok(Verdict::TooLow)
+        } else if text.contains("That's not the right answer") {
+            Ok(Verdict::Wrong)
+        } else if text.contains("answer too recently") {
+            let wait = text
+                .split_once("You have ")
+                .or_else(|| text.split_once("you have "))
+                .and_then(|(_, rest)| rest.split_once(" left"))
+                .ok_or_else(|| anyhow!("Couldn't find how long to wait in {text:?}"))?
+                .0;
+            let mut seconds = 0;
+            for part in wait.split_whitespace() {
+                let Some((amount, unit)) = [("h", 3600), ("m", 60), ("s", 1)]
+                    .into_iter()
+                    .find_map(|(suffix, unit)| Some((part.strip_suffix(suffix)?, unit)))
+                else {
+                    bail!("Couldn't read the wait {wait:?}");
+                };
+                let amount: u64 = amount
+                    .parse()
+                    .with_context(|| format!("Couldn't read the wait {wait:?}"))?;
+                seconds += amount * unit;
+            }
+            Ok(Verdict::Wait(Duration::from_secs(seconds)))
+        } else if text.contains("Did you already complete it") {
+            Ok(Verdict::AlreadySolved)
+        } else {
+            bail!("Couldn't understand the response: {:?}", text.trim())
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wait(_) => "wait",
+            Verdict::AlreadySolved => "already-solved",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "That's the right answer"),
+            Verdict::Wrong => write!(f, "That's not the right answer"),
+            Verdict::TooHigh => write!(f, "That's not the right answer; it's too high"),
+            Verdict::TooLow => write!(f, "That's not the right answer; it's too low"),
+            Verdict::Wait(wait) => write!(
+                f,
+                "An answer was given too recently; try again in {}s",
+                wait.as_secs()
+            ),
+            Verdict::AlreadySolved => write!(f, "That part has already been solved"),
+        }
+    }
+}
+
+/// One answer that was submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Every answer submitted so far that the site gave a verdict on.
+#[derive(Debug, Default)]
+pub struct History {
+    pub submissions: Vec<Submission>,
+}
+
+impl History {
+    /// Reads the history from `path`, which doesn't have to exist yet.
+    pub fn load(path: &Path) -> Result<History> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(History::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        let mut submissions = vec![];
+        for (i, line) in text.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+            let parse = || -> Option<Submission> {
+                let mut fields = line.splitn(4, '\t');
+                let day = fields.next()?.parse().ok()?;
+                let part = fields.next()?.parse().ok()?;
+                let verdict = match fields.next()? {
+                    "correct" => Verdict::Correct,
+                    "wrong" => Verdict::Wrong,
+                    "too-high" => Verdict::TooHigh,
+                    "too-low" => Verdict::TooLow,
+                    _ => return None,
+                };
+                let answer = fields.next()?.to_string();
+                Some(Submission {
+                    day,
+                    part,
+                    answer,
+                    verdict,
+                })
+            };
+            let submission = parse().ok_or_else(|| {
+                anyhow!(
+                    "{}, line {}: bad submission {line:?}",
+                    path.display(),
+                    i + 1
+                )
+            })?;
+            submissions.push(submission);
+        }
+
+        Ok(History { submissions })
+    }
+
+    /// Writes the history to `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut text = String::new();
+        for s in &self.submissions {
+            let verdict = s.verdict.name();
+            text.push_str(&format!("{}\t{}\t{verdict}\t{}\n", s.day, s.part, s.answer));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Remembers the verdict on a submission.  Verdicts that didn't check the answer are dropped.
+    pub fn record(&mut self, day: u32, part: u32, answer: &str, verdict: Verdict) {
+        if matches!(verdict, Verdict::Wait(_) | Verdict::AlreadySolved) {
+            return;
+        }
+        self.submissions.push(Submission {
+            day,
+            part,
+            answer: answer.to_string(),
+            verdict,
+        });
+    }
+
+    /// What is already known about `answer`, if enough to not submit it: the verdict it got
+    /// before, or one implied by the bounds from earlier answers.
+    ///
+    /// ```rust
+    /// # use aoc_2022::submit::*;
+    /// let mut history = History::default();
+    /// history.record(1, 1, "5000", Verdict::TooHigh);
+    /// history.record(1, 1, "1000", Verdict::TooLow);
+    /// history.record(1, 1, "abc", Verdict::Wrong);
+    /// assert_eq!(history.check(1, 1, "6000"), Some(Verdict::TooHigh));
+    /// assert_eq!(history.check(1, 1, "1000"), Some(Verdict::TooLow));
+    /// assert_eq!(history.check(1, 1, "abc"), Some(Verdict::Wrong));
+    /// assert_eq!(history.check(1, 1, "3000"), None);
+    /// assert_eq!(history.check(1, 2, "6000"), None);
+    /// ```
+    pub fn check(&self, day: u32, part: u32, answer: &str) -> Option<Verdict> {
+        let earlier = self
+            .submissions
+            .iter()
+            .filter(|s| s.day == day && s.part == part);
+
+        let number = answer.parse::<i64>().ok();
+        for s in earlier {
+            if s.answer == answer {
+                return Some(s.verdict);
+            }
+            if s.verdict == Verdict::Correct {
+                return Some(Verdict::Wrong);
+            }
+            let Some((number, bound)) = number.zip(s.answer.parse::<i64>().ok()) else {
+                continue;
+            };
+            match s.verdict {
+                Verdict::TooHigh if number >= bound => return Some(Verdict::TooHigh),
+                Verdict::TooLow if number <= bound => return Some(Verdict::TooLow),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// The correct answer to a part, if it has been found.
+    pub fn correct(&self, day: u32, part: u32) -> Option<&str> {
+        self.submissions
+            .iter()
+            .find(|s| s.day == day && s.part == part && s.verdict == Verdict::Correct)
+            .map(|s| s.answer.as_str())
+    }
+}
+
+/// Submits `answer` to `part` of `day`'s puzzle, keeping track of the verdicts in the history at
+/// `path`.  An answer the history already knows is correct isn't submitted again, and any other
+/// answer it knows about is turned down without being submitted.
+pub fn submit(
+    client: &mut Client,
+    path: &Path,
+    day: u32,
+    part: u32,
+    answer: &str,
+) -> Result<Verdict> {
+    let mut history = History::load(path)?;
+    if let Some(correct) = history.correct(day, part) {
+        if correct == answer {
+            return Ok(Verdict::Correct);
+        }
+        bail!("Not submitting, as this part has been solved already with {correct}");
+    }
+    if let Some(verdict) = history.check(day, part, answer) {
+        bail!("Not submitting, as this is already known: {verdict}");
+    }
+
+    let verdict = client.submit_answer(day, part, answer)?;
+    history.record(day, part, answer, verdict);
+    history.save(path)?;
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockServer, SESSION};
+    use std::env;
+
+    #[test]
+    fn test_parse_real_pages() {
+        let pages = [
+            (
+                "<main>\n<article><p>That's the right answer!  You are <span class=\"day-success\">\
+                 one gold star</span> closer to collecting enough star fruit.</p></article>\n</main>",
+                Verdict::Correct,
+            ),
+            (
+                "<article><p>That's not the right answer.  If you're stuck, make sure you're using \
+                 the full input data; there are also some general tips on the \
+                 <a href=\"/2022/about\">about page</a>.  Please wait one minute before trying \
+                 again. [<a href=\"/2022/day/1\">Return to Day 1</a>]</p></article>",
+                Verdict::Wrong,
+            ),
+            (
+                "<article><p>That's not the right answer; your answer is too low.  Please wait \
+                 one minute before trying again.</p></article>",
+                Verdict::TooLow,
+            ),
+            (
+                "<article><p>You gave an answer too recently; you have to wait after submitting an \
+                 answer before trying again.  You have 37s left to wait.</p></article>",
+                Verdict::Wait(Duration::from_secs(37)),
+            ),
+            (
+                "<article><p>You don't seem to be solving the right level.  Did you already \
+                 complete it? [<a href=\"/2022/day/1\">Return to Day 1</a>]</p></article>",
+                Verdict::AlreadySolved,
+            ),
+        ];
+        for (page, verdict) in pages {
+            assert_eq!(Verdict::parse(page).unwrap(), verdict);
+        }
+        assert!(Verdict::parse("<article><p>Something else</p></article>").is_err());
+
+        // units that aren't a single ASCII letter are errors, not panics
+        for wait in ["1m 5秒", "5é", "10 s", "ms"] {
+            let page = format!("<p>You gave an answer too recently. You have {wait} left.</p>");
+            assert!(Verdict::parse(&page).is_err(), "{wait}");
+        }
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let dir = env::temp_dir().join(format!("aoc-2022-history-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("history.txt");
+        assert!(History::load(&path).unwrap().submissions.is_empty());
+
+        let mut history = History::default();
+        history.record(5, 1, "CMZ", Verdict::Wrong);
+        history.record(5, 1, "MCD", Verdict::Wait(Duration::from_secs(60)));
+        history.record(5, 1, "QMBMJDFTD", Verdict::Correct);
+        history.save(&path).unwrap();
+
+        let loaded = History::load(&path).unwrap();
+        assert_eq!(loaded.submissions, history.submissions);
+        assert_eq!(loaded.correct(5, 1), Some("QMBMJDFTD"));
+        assert_eq!(loaded.check(5, 1, "MCD"), Some(Verdict::Wrong));
+
+        fs::write(&path, "5\t1\tmaybe\tCMZ\n").unwrap();
+        assert!(History::load(&path).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = MockServer::start();
+        let mut client = Client::new(SESSION, server.dir()).with_interval(Duration::ZERO);
+        client = client.with_base_url(&server.url());
+        let answer = MockServer::answer(2, 1);
+
+        let low = (answer - 1).to_string();
+        assert_eq!(client.submit_answer(2, 1, &low).unwrap(), Verdict::TooLow);
+        let high = (answer + 1).to_string();
+        assert_eq!(client.submit_answer(2, 1, &high).unwrap(), Verdict::TooHigh);
+        assert_eq!(client.submit_answer(2, 1, "x").unwrap(), Verdict::Wrong);
+        let answer = answer.to_string();
+        assert_eq!(
+            client.submit_answer(2, 1, &answer).unwrap(),
+            Verdict::Correct
+        );
+
+        let wait = client.submit_answer(MockServer::SLOW_DAY, 1, "1").unwrap();
+        assert_eq!(wait, Verdict::Wait(Duration::from_secs(65)));
+        assert!(client
+            .submit_answer(MockServer::BROKEN_DAY, 1, "1")
+            .is_err());
+        assert!(client.submit_answer(30, 1, "1").is_err());
+        assert!(client.submit_answer(2, 3, "1").is_err());
+        assert_eq!(server.requests(), 7);
+
+        let mut client = Client::new("expired", server.dir()).with_base_url(&server.url());
+        let err = client.submit_answer(2, 1, &answer).unwrap_err().to_string();
+        assert!(err.starts_with("The session cookie was rejected"), "{err}");
+    }
+
+    #[test]
+    fn test_submit_with_history() {
+        let server = MockServer::start();
+        let mut client = Client::new(SESSION, server.dir())
+            .with_base_url(&server.url())
+            .with_interval(Duration::ZERO);
+        let path = server.dir().join("history.txt");
+        let answer = MockServer::answer(4, 2);
+        let mut submit = |answer: i64| submit(&mut client, &path, 4, 2, &answer.to_string());
+
+        assert_eq!(submit(answer + 10).unwrap(), Verdict::TooHigh);
+        assert_eq!(submit(answer - 10).unwrap(), Verdict::TooLow);
+        assert_eq!(server.requests(), 2);
+        // both of these are already known to be wrong
+        let err = submit(answer + 20).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Not submitting, as this is already known: That's not the right answer; it's too high"
+        );
+        assert!(submit(answer - 10).is_err());
+        assert_eq!(server.requests(), 2);
+
+        assert_eq!(submit(answer).unwrap(), Verdict::Correct);
+        assert_eq!(submit(answer).unwrap(), Verdict::Correct);
+        assert!(submit(answer + 1).is_err());
+        assert_eq!(server.requests(), 3);
+
+        let history = History::load(&path).unwrap();
+        assert_eq!(history.submissions.len(), 3);
+        assert_eq!(history.correct(4, 2), Some(answer.to_string().as_str()));
+    }
+}
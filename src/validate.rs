@@ -0,0 +1,400 @@
+//! Checks that an input is well-formed before it's handed to a solver.  Unlike the solvers, which
+//! give up at the first problem, every problem in the input is reported.
+
+use std::{collections::HashSet, fmt};
+
+use anyhow::{bail, Result};
+
+use crate::{
+    day5::{check_stacks, sections, Command, Crane},
+    day6::find_marker,
+};
+
+/// A problem found in an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// 1-indexed line the problem is on, if it's on one in particular.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Finding {
+    fn at(line: usize, message: impl Into<String>) -> Self {
+        Finding {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+
+    fn whole(message: impl Into<String>) -> Self {
+        Finding {
+            line: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Each line of `input` along with its 1-indexed line number.
+fn numbered(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines().enumerate().map(|(i, line)| (i + 1, line))
+}
+
+/// Checks `input` against the format of `day`'s puzzle, returning every problem found.
+///
+/// ```rust
+/// # use aoc_2022::validate::*;
+/// let input = "2-4,6-8\n\
+///              7-3,4-5\n\
+///              5-7,7-9,1-2\n\
+///              2-8,3-7";
+/// let findings: Vec<_> = validate(4, input).unwrap().iter().map(ToString::to_string).collect();
+/// assert_eq!(
+///     findings,
+///     [
+///         "line 2: range 7-3 ends before it starts",
+///         "line 3: expected a pair of ranges like 2-4,6-8, found \"5-7,7-9,1-2\"",
+///     ]
+/// );
+/// ```
+pub fn validate(day: u32, input: &str) -> Result<Vec<Finding>> {
+    Ok(match day {
+        1 => day1(input),
+        2 => day2(input),
+        3 => day3(input),
+        4 => day4(input),
+        5 => day5(input),
+        6 => day6(input),
+        _ => bail!("There is no validator for day {day}"),
+    })
+}
+
+/// Every line is a number of calories, with single blank lines between elves.
+pub fn day1(input: &str) -> Vec<Finding> {
+    let mut findings = vec![];
+    let mut elves = 0;
+    let mut total: Option<u32> = None;
+    let mut last_blank = None;
+    for (n, line) in numbered(input) {
+        if line.is_empty() {
+            if total.is_none() {
+                findings.push(Finding::at(n, "blank line without an elf before it"));
+            }
+            total = None;
+            last_blank = Some(n);
+            continue;
+        }
+
+        if total.is_none() {
+            elves += 1;
+        }
+        match line.parse::<u32>() {
+            Ok(calories) => match total.unwrap_or_default().checked_add(calories) {
+                Some(sum) => total = Some(sum),
+                None => {
+                    findings.push(Finding::at(
+                        n,
+                        "this elf carries too many calories to count",
+                    ));
+                    total = Some(u32::MAX);
+                }
+            },
+            Err(_) => {
+                findings.push(Finding::at(
+                    n,
+                    format!("expected a number of calories, found {line:?}"),
+                ));
+                total.get_or_insert(0);
+            }
+        }
+    }
+
+    if let (None, Some(blank)) = (total, last_blank) {
+        if elves > 0 && !findings.iter().any(|f| f.line == Some(blank)) {
+            findings.push(Finding::at(blank, "blank line after the last elf"));
+        }
+    }
+    if elves < 3 {
+        findings.push(Finding::whole(format!(
+            "found {elves} elves, but part 2 needs at least three"
+        )));
+    }
+    findings
+}
+
+/// Every line is a round like `A X`.
+pub fn day2(input: &str) -> Vec<Finding> {
+    numbered(input)
+        .filter(|(_, line)| !matches!(line.as_bytes(), [b'A'..=b'C', b' ', b'X'..=b'Z']))
+        .map(|(n, line)| Finding::at(n, format!("expected a round like \"A X\", found {line:?}")))
+        .collect()
+}
+
+/// Every line is a rucksack of letters with exactly one item in both compartments, and each group
+/// of three rucksacks has exactly one item in common.
+pub fn day3(input: &str) -> Vec<Finding> {
+    let items = |text: &str| -> HashSet<char> { text.chars().collect() };
+    let listed = |shared: &HashSet<char>| {
+        let mut shared: Vec<char> = shared.iter().copied().collect();
+        shared.sort_unstable();
+        shared.into_iter().collect::<String>()
+    };
+
+    let mut findings = vec![];
+    let lines: Vec<(usize, &str)> = numbered(input).collect();
+    for &(n, line) in &lines {
+        if let Some((column, item)) = line
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !c.is_ascii_alphabetic())
+        {
+            findings.push(Finding::at(
+                n,
+                format!("item {item:?} at column {} has no priority", column + 1),
+            ));
+            continue;
+        }
+        if line.is_empty() || !line.len().is_multiple_of(2) {
+            findings.push(Finding::at(
+                n,
+                format!(
+                    "{} items can't be split evenly into two compartments",
+                    line.len()
+                ),
+            ));
+            continue;
+        }
+
+        let (first, second) = line.split_at(line.len() / 2);
+        let shared: HashSet<char> = &items(first) & &items(second);
+        match shared.len() {
+            1 => {}
+            0 => findings.push(Finding::at(n, "no item is in both compartments")),
+            _ => findings.push(Finding::at(
+                n,
+                format!("both compartments hold {}", listed(&shared)),
+            )),
+        }
+    }
+
+    for group in lines.chunks_exact(3) {
+        let badges = &(&items(group[0].1) & &items(group[1].1)) & &items(group[2].1);
+        match badges.len() {
+            1 => {}
+            0 => findings.push(Finding::at(group[0].0, "this group has no item in common")),
+            _ => findings.push(Finding::at(
+                group[0].0,
+                format!("this group has {} in common", listed(&badges)),
+            )),
+        }
+    }
+    if !lines.len().is_multiple_of(3) {
+        findings.push(Finding::whole(format!(
+            "{} rucksacks can't be split into groups of three",
+            lines.len()
+        )));
+    }
+
+    findings.sort_by_key(|finding| finding.line.unwrap_or(usize::MAX));
+    findings
+}
+
+/// Every line is a pair of ranges like `2-4,6-8`, each starting no later than it ends.
+pub fn day4(input: &str) -> Vec<Finding> {
+    let range = |text: &str| -> Option<(u32, u32)> {
+        let (start, end) = text.split_once('-')?;
+        Some((start.parse().ok()?, end.parse().ok()?))
+    };
+
+    let mut findings = vec![];
+    for (n, line) in numbered(input) {
+        let Some(ranges) = line
+            .split_once(',')
+            .and_then(|(a, b)| Some([range(a)?, range(b)?]))
+        else {
+            findings.push(Finding::at(
+                n,
+                format!("expected a pair of ranges like 2-4,6-8, found {line:?}"),
+            ));
+            continue;
+        };
+
+        for (start, end) in ranges {
+            if start > end {
+                findings.push(Finding::at(
+                    n,
+                    format!("range {start}-{end} ends before it starts"),
+                ));
+            }
+        }
+    }
+    findings
+}
+
+/// A drawing of stacks with every crate lined up above a stack number, then a blank line, then
+/// commands that only ever take crates that are there from stacks that exist.
+pub fn day5(input: &str) -> Vec<Finding> {
+    let sections = sections(input);
+
+    let mut findings = vec![];
+    let stacks = match check_stacks(&sections.drawing) {
+        Ok(stacks) => Some(stacks),
+        Err(errors) => {
+            findings.extend(errors.into_iter().map(|(n, e)| Finding::at(n, e)));
+            None
+        }
+    };
+    findings.extend(
+        sections
+            .gaps
+            .iter()
+            .map(|&n| Finding::at(n, "unexpected blank line between commands")),
+    );
+
+    let mut commands = vec![];
+    let mut command_lines = vec![];
+    for (n, line) in sections.commands {
+        match Command::parse(line) {
+            Ok(("", command)) => {
+                commands.push(command);
+                command_lines.push(n);
+            }
+            _ => findings.push(Finding::at(
+                n,
+                format!("expected a command like \"move 1 from 2 to 3\", found {line:?}"),
+            )),
+        }
+    }
+
+    // commands can only be checked against the stacks if the drawing could be read.
+    if let Some(stacks) = stacks {
+        if let Err(errors) = Crane::new(stacks, commands).validate() {
+            findings.extend(
+                errors
+                    .into_iter()
+                    .map(|e| Finding::at(command_lines[e.index], e.kind.to_string())),
+            );
+        }
+    }
+
+    findings.sort_by_key(|finding| finding.line.unwrap_or(usize::MAX));
+    findings
+}
+
+/// A single line holding both a start-of-packet and a start-of-message marker.
+pub fn day6(input: &str) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = numbered(input)
+        .skip(1)
+        .map(|(n, _)| Finding::at(n, "the datastream should all be on one line"))
+        .collect();
+
+    let stream = input.lines().next().unwrap_or_default();
+    for (n, name) in [(4, "start-of-packet"), (14, "start-of-message")] {
+        if find_marker(stream, n).is_none() {
+            findings.push(Finding::whole(format!(
+                "no {name} marker of {n} distinct characters"
+            )));
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::generate;
+
+    #[test]
+    fn test_inputs_are_clean() {
+        let inputs = [
+            include_str!("../input/2022/day1.txt"),
+            include_str!("../input/2022/day2.txt"),
+            include_str!("../input/2022/day3.txt"),
+            include_str!("../input/2022/day4.txt"),
+            include_str!("../input/2022/day5.txt"),
+            include_str!("../input/2022/day6.txt"),
+        ];
+        for (day, input) in (1..).zip(inputs) {
+            assert_eq!(validate(day, input).unwrap(), vec![], "day {day}");
+            for seed in 0..5 {
+                let generated = generate(day, seed, Some(30)).unwrap();
+                assert_eq!(
+                    validate(day, &generated.input).unwrap(),
+                    vec![],
+                    "day {day}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_reports_everything() {
+        let findings = |day, input| -> Vec<String> {
+            validate(day, input)
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+
+        assert_eq!(
+            findings(1, "1000\n\n\n2000\nlots\n\n"),
+            [
+                "line 3: blank line without an elf before it",
+                "line 5: expected a number of calories, found \"lots\"",
+                "line 6: blank line after the last elf",
+                "found 2 elves, but part 2 needs at least three",
+            ]
+        );
+        assert_eq!(
+            findings(3, "abca\nabab\nab1c\nxyz"),
+            [
+                "line 1: this group has ab in common",
+                "line 2: both compartments hold ab",
+                "line 3: item '1' at column 3 has no priority",
+                "line 4: 3 items can't be split evenly into two compartments",
+                "4 rucksacks can't be split into groups of three",
+            ]
+        );
+        assert_eq!(
+            findings(
+                5,
+                "[A]     [C]\n[B][D]\n 1   2   3 \n\nmove 1 from 1 to 4\nmove 2 from 2 to 1\nmove one\n\n\nmove 3 from 2 to 3"
+            ),
+            [
+                "line 1: crate [A] at column 1 is not sitting on top of stack 1",
+                "line 1: crate [C] at column 9 is not sitting on top of stack 3",
+                "line 2: crate [B][D] at column 1 is above more than one stack number",
+                "line 7: expected a command like \"move 1 from 2 to 3\", found \"move one\"",
+                "line 8: unexpected blank line between commands",
+            ]
+        );
+        assert_eq!(
+            findings(
+                5,
+                "[A]\n 1   2 \n\nmove 1 from 1 to 3\nmove 2 from 1 to 2\nmove 1 from 1 to 2"
+            ),
+            [
+                "line 4: stack 3 does not exist",
+                "line 5: attempted to remove 2 crates from stack 1, which only has 1",
+                "line 6: attempted to remove a crate from empty stack 1",
+            ]
+        );
+        assert_eq!(
+            findings(6, "abcabc\nd"),
+            [
+                "line 2: the datastream should all be on one line",
+                "no start-of-packet marker of 4 distinct characters",
+                "no start-of-message marker of 14 distinct characters",
+            ]
+        );
+    }
+}